@@ -0,0 +1,362 @@
+use crate::location::Location;
+use crate::measurement::Distance;
+use crate::sphere::{arc_length, central_angle, Vector3};
+
+/// Entries a leaf holds before it's split in two.
+const LEAF_CAPACITY: usize = 8;
+
+/// The largest share of a branch's entries that one child may hold before inserts rebuild the
+/// branch. This keeps the depth logarithmic however the inserts are ordered.
+const MAX_CHILD_SHARE: f64 = 0.75;
+
+#[derive(Debug, Clone)]
+struct Entry<T> {
+    vector: Vector3,
+    location: Location,
+    item: T,
+}
+
+/// A spherical cap containing every entry below a node, stored as a centre and an angular radius.
+#[derive(Debug, Clone, Copy)]
+struct Ball {
+    centre: Vector3,
+    radius: f64,
+}
+
+impl Ball {
+    fn bounding<T>(entries: &[Entry<T>]) -> Self {
+        let sum = entries
+            .iter()
+            .fold(Vector3::default(), |sum, entry| sum.add(&entry.vector));
+        let centre = sum
+            .normalize()
+            .or_else(|| entries.first().map(|entry| entry.vector))
+            .unwrap_or_default();
+        let radius = entries
+            .iter()
+            .map(|entry| centre.angle(&entry.vector))
+            .fold(0., f64::max);
+        Self { centre, radius }
+    }
+
+    fn merge(a: &Ball, b: &Ball) -> Self {
+        let centre = a.centre.add(&b.centre).normalize().unwrap_or(a.centre);
+        let radius = f64::max(
+            centre.angle(&a.centre) + a.radius,
+            centre.angle(&b.centre) + b.radius,
+        );
+        Self { centre, radius }
+    }
+
+    /// The smallest angle from `vector` to anything inside the ball.
+    fn min_angle(&self, vector: &Vector3) -> f64 {
+        (self.centre.angle(vector) - self.radius).max(0.)
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Node<T> {
+    Leaf(Ball, Vec<Entry<T>>),
+    /// A ball around both children, the children, and the number of entries below.
+    Branch(Ball, Box<[Node<T>; 2]>, usize),
+}
+
+impl<T> Node<T> {
+    fn ball(&self) -> &Ball {
+        match self {
+            Node::Leaf(ball, _) | Node::Branch(ball, _, _) => ball,
+        }
+    }
+
+    fn len(&self) -> usize {
+        match self {
+            Node::Leaf(_, entries) => entries.len(),
+            Node::Branch(_, _, len) => *len,
+        }
+    }
+
+    /// Moves every entry below this node into `entries`.
+    fn into_entries(self, entries: &mut Vec<Entry<T>>) {
+        match self {
+            Node::Leaf(_, mut leaf) => entries.append(&mut leaf),
+            Node::Branch(_, children, _) => {
+                let [left, right] = *children;
+                left.into_entries(entries);
+                right.into_entries(entries);
+            }
+        }
+    }
+
+    /// Builds a subtree, splitting at the median of the axis with the largest spread.
+    fn build(mut entries: Vec<Entry<T>>) -> Self {
+        if entries.len() <= LEAF_CAPACITY {
+            return Node::Leaf(Ball::bounding(&entries), entries);
+        }
+
+        let spread = |axis: fn(&Vector3) -> f64| {
+            let (min, max) = entries.iter().fold((f64::MAX, f64::MIN), |(min, max), e| {
+                (min.min(axis(&e.vector)), max.max(axis(&e.vector)))
+            });
+            max - min
+        };
+        let axes: [fn(&Vector3) -> f64; 3] = [|v| v.x, |v| v.y, |v| v.z];
+        let axis = axes
+            .into_iter()
+            .max_by(|a, b| spread(*a).total_cmp(&spread(*b)))
+            .unwrap_or(axes[0]);
+        entries.sort_by(|a, b| axis(&a.vector).total_cmp(&axis(&b.vector)));
+
+        let len = entries.len();
+        let right = entries.split_off(len / 2);
+        let children = Box::new([Node::build(entries), Node::build(right)]);
+        Node::Branch(
+            Ball::merge(children[0].ball(), children[1].ball()),
+            children,
+            len,
+        )
+    }
+
+    fn insert(&mut self, entry: Entry<T>) {
+        let unbalanced = match self {
+            Node::Leaf(ball, entries) => {
+                ball.radius = ball.radius.max(ball.centre.angle(&entry.vector));
+                entries.push(entry);
+                entries.len() > LEAF_CAPACITY
+            }
+            Node::Branch(ball, children, len) => {
+                ball.radius = ball.radius.max(ball.centre.angle(&entry.vector));
+                *len += 1;
+                // Descend into the child that has to grow the least
+                let growth = |node: &Node<T>| {
+                    let ball = node.ball();
+                    ball.centre.angle(&entry.vector) - ball.radius
+                };
+                let index = usize::from(growth(&children[1]) < growth(&children[0]));
+                children[index].insert(entry);
+                children[index].len() as f64 > MAX_CHILD_SHARE * *len as f64
+            }
+        };
+        if unbalanced {
+            let node = core::mem::replace(self, Node::Leaf(Ball::bounding::<T>(&[]), Vec::new()));
+            let mut entries = Vec::with_capacity(node.len());
+            node.into_entries(&mut entries);
+            *self = Node::build(entries);
+        }
+    }
+
+    fn remove(
+        &mut self,
+        vector: &Vector3,
+        matches: &mut impl FnMut(&Entry<T>) -> bool,
+    ) -> Option<T> {
+        match self {
+            Node::Leaf(_, entries) => {
+                let index = entries.iter().position(&mut *matches)?;
+                Some(entries.swap_remove(index).item)
+            }
+            Node::Branch(_, children, len) => {
+                let removed = children
+                    .iter_mut()
+                    .filter(|child| child.ball().min_angle(vector) <= 1e-9)
+                    .find_map(|child| child.remove(vector, matches));
+                if removed.is_some() {
+                    *len -= 1;
+                }
+                removed
+            }
+        }
+    }
+
+    fn for_each<'a>(&'a self, f: &mut impl FnMut(&'a Entry<T>)) {
+        match self {
+            Node::Leaf(_, entries) => entries.iter().for_each(f),
+            Node::Branch(_, children, _) => children.iter().for_each(|child| child.for_each(f)),
+        }
+    }
+
+    /// Keeps the `k` closest entries in `best`, sorted by angle.
+    fn nearest<'a>(&'a self, vector: &Vector3, k: usize, best: &mut Vec<(f64, &'a Entry<T>)>) {
+        let worst = |best: &Vec<(f64, &Entry<T>)>| {
+            if best.len() < k {
+                f64::INFINITY
+            } else {
+                best[best.len() - 1].0
+            }
+        };
+        if self.ball().min_angle(vector) > worst(best) {
+            return;
+        }
+
+        match self {
+            Node::Leaf(_, entries) => {
+                for entry in entries {
+                    let angle = vector.angle(&entry.vector);
+                    if angle < worst(best) {
+                        let index = best.partition_point(|(a, _)| *a <= angle);
+                        best.insert(index, (angle, entry));
+                        best.truncate(k);
+                    }
+                }
+            }
+            Node::Branch(_, children, _) => {
+                let (first, second) = if children[1].ball().min_angle(vector)
+                    < children[0].ball().min_angle(vector)
+                {
+                    (&children[1], &children[0])
+                } else {
+                    (&children[0], &children[1])
+                };
+                first.nearest(vector, k, best);
+                second.nearest(vector, k, best);
+            }
+        }
+    }
+
+    fn within<'a>(&'a self, vector: &Vector3, radius: f64, found: &mut Vec<(f64, &'a Entry<T>)>) {
+        if self.ball().min_angle(vector) > radius {
+            return;
+        }
+        match self {
+            Node::Leaf(_, entries) => found.extend(
+                entries
+                    .iter()
+                    .map(|entry| (vector.angle(&entry.vector), entry))
+                    .filter(|(angle, _)| *angle <= radius),
+            ),
+            Node::Branch(_, children, _) => {
+                for child in children.iter() {
+                    child.within(vector, radius, found);
+                }
+            }
+        }
+    }
+}
+
+/// A ball tree over the unit sphere for nearest neighbour and radius queries.
+///
+/// Distances are great circle distances, so queries work the same across the
+/// antimeridian and near the poles.
+#[derive(Debug, Clone)]
+pub struct BallTree<T> {
+    root: Node<T>,
+    len: usize,
+}
+
+impl<T> Default for BallTree<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> BallTree<T> {
+    pub fn new() -> Self {
+        Self {
+            root: Node::Leaf(Ball::bounding::<T>(&[]), Vec::new()),
+            len: 0,
+        }
+    }
+
+    /// Builds a balanced tree from all items at once, which is faster and gives better queries than inserting one by one.
+    pub fn bulk_load(items: impl IntoIterator<Item = (Location, T)>) -> Self {
+        let entries: Vec<Entry<T>> = items
+            .into_iter()
+            .map(|(location, item)| Entry {
+                vector: Vector3::from_location(&location),
+                location,
+                item,
+            })
+            .collect();
+        let len = entries.len();
+        Self {
+            root: Node::build(entries),
+            len,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Adds an item. Subtrees are rebuilt as they become lopsided, so inserting in any order
+    /// keeps queries fast, though `bulk_load` is still quicker for many items at once.
+    pub fn insert(&mut self, location: Location, item: T) {
+        let entry = Entry {
+            vector: Vector3::from_location(&location),
+            location,
+            item,
+        };
+        if self.len == 0 {
+            self.root = Node::build(vec![entry]);
+        } else {
+            self.root.insert(entry);
+        }
+        self.len += 1;
+    }
+
+    /// Removes an item stored at `location` and returns it.
+    pub fn remove(&mut self, location: &Location, item: &T) -> Option<T>
+    where
+        T: PartialEq,
+    {
+        let vector = Vector3::from_location(location);
+        let removed = self.root.remove(&vector, &mut |entry| {
+            entry.location == *location && entry.item == *item
+        });
+        if removed.is_some() {
+            self.len -= 1;
+        }
+        removed
+    }
+
+    /// Returns every location and item in the tree, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = (&Location, &T)> {
+        let mut entries = Vec::with_capacity(self.len);
+        self.root.for_each(&mut |entry| entries.push(entry));
+        entries
+            .into_iter()
+            .map(|entry| (&entry.location, &entry.item))
+    }
+
+    pub fn nearest(&self, location: &Location) -> Option<(&T, Distance)> {
+        self.k_nearest(location, 1).into_iter().next()
+    }
+
+    /// Returns up to `k` items closest to `location`, nearest first.
+    pub fn k_nearest(&self, location: &Location, k: usize) -> Vec<(&T, Distance)> {
+        let k = k.min(self.len);
+        if k == 0 {
+            return Vec::new();
+        }
+        let mut best = Vec::with_capacity(k + 1);
+        self.root
+            .nearest(&Vector3::from_location(location), k, &mut best);
+        best.into_iter()
+            .map(|(angle, entry)| (&entry.item, arc_length(angle)))
+            .collect()
+    }
+
+    /// Returns every item within `radius` of `location`, nearest first.
+    pub fn within(&self, location: &Location, radius: &Distance) -> Vec<(&T, Distance)> {
+        let mut found = Vec::new();
+        self.root.within(
+            &Vector3::from_location(location),
+            central_angle(radius),
+            &mut found,
+        );
+        found.sort_by(|a, b| a.0.total_cmp(&b.0));
+        found
+            .into_iter()
+            .map(|(angle, entry)| (&entry.item, arc_length(angle)))
+            .collect()
+    }
+}
+
+impl<T> FromIterator<(Location, T)> for BallTree<T> {
+    fn from_iter<I: IntoIterator<Item = (Location, T)>>(iter: I) -> Self {
+        Self::bulk_load(iter)
+    }
+}
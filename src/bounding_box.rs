@@ -0,0 +1,75 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::location::Location;
+
+/// A latitude/longitude box. When `west` is greater than `east` the box crosses
+/// the antimeridian, e.g. `west: 170., east: -170.` covers 20 degrees of longitude.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct BoundingBox {
+    pub south: f64,
+    pub west: f64,
+    pub north: f64,
+    pub east: f64,
+}
+
+impl BoundingBox {
+    pub fn new(south: f64, west: f64, north: f64, east: f64) -> Self {
+        Self {
+            south,
+            west,
+            north,
+            east,
+        }
+    }
+
+    /// A zero sized box around a single location.
+    pub fn from_location(location: &Location) -> Self {
+        Self::new(
+            location.latitude,
+            location.longitude,
+            location.latitude,
+            location.longitude,
+        )
+    }
+
+    pub fn crosses_antimeridian(&self) -> bool {
+        self.west > self.east
+    }
+
+    /// Splits a box crossing the antimeridian into its western part (ending at 180)
+    /// and its eastern part (starting at -180). Other boxes are returned unchanged.
+    pub fn split_antimeridian(&self) -> (BoundingBox, Option<BoundingBox>) {
+        if self.crosses_antimeridian() {
+            (
+                Self::new(self.south, self.west, self.north, 180.),
+                Some(Self::new(self.south, -180., self.north, self.east)),
+            )
+        } else {
+            (*self, None)
+        }
+    }
+
+    pub fn contains(&self, location: &Location) -> bool {
+        let lng = location.longitude;
+        let in_longitude = if self.crosses_antimeridian() {
+            lng >= self.west || lng <= self.east
+        } else {
+            lng >= self.west && lng <= self.east
+        };
+        in_longitude && location.latitude >= self.south && location.latitude <= self.north
+    }
+
+    pub fn intersects(&self, other: &BoundingBox) -> bool {
+        let overlaps = |a: &BoundingBox, b: &BoundingBox| {
+            a.west <= b.east && b.west <= a.east && a.south <= b.north && b.south <= a.north
+        };
+        let (a1, a2) = self.split_antimeridian();
+        let (b1, b2) = other.split_antimeridian();
+        [Some(a1), a2]
+            .iter()
+            .flatten()
+            .any(|a| [Some(b1), b2].iter().flatten().any(|b| overlaps(a, b)))
+    }
+}
@@ -0,0 +1,215 @@
+use libm::{ceil, cos};
+
+use crate::circle::Circle;
+use crate::geometry::{Geometry, LineString, Polygon};
+use crate::location::Location;
+use crate::measurement::Distance;
+use crate::sphere::{bearing, central_angle, destination, ring_contains, wrap_longitude, Vector3};
+
+/// Points around `centre` from `from` degrees, sweeping `sweep` degrees (negative is counter-clockwise).
+fn arc(
+    centre: &Location,
+    from: f64,
+    sweep: f64,
+    angle: f64,
+    steps: usize,
+    out: &mut Vec<Location>,
+) {
+    for step in 0..=steps {
+        let bearing = from + sweep * step as f64 / steps as f64;
+        out.push(destination(centre, bearing, angle));
+    }
+}
+
+/// Clips a planar ring of `(longitude, latitude)` to the side of a meridian where `inside` holds.
+fn clip(ring: &[(f64, f64)], boundary: f64, inside: impl Fn(f64) -> bool) -> Vec<(f64, f64)> {
+    let mut output = Vec::new();
+    for (i, &current) in ring.iter().enumerate() {
+        let previous = ring[(i + ring.len() - 1) % ring.len()];
+        let crossing = || {
+            let t = (boundary - previous.0) / (current.0 - previous.0);
+            (boundary, previous.1 + t * (current.1 - previous.1))
+        };
+        match (inside(previous.0), inside(current.0)) {
+            (true, true) => output.push(current),
+            (true, false) => output.push(crossing()),
+            (false, true) => {
+                output.push(crossing());
+                output.push(current);
+            }
+            (false, false) => {}
+        }
+    }
+    output
+}
+
+fn closed_polygon(points: impl IntoIterator<Item = (f64, f64)>) -> Polygon {
+    let mut points: Vec<Location> = points
+        .into_iter()
+        .map(|(longitude, latitude)| Location::from(latitude, longitude))
+        .collect();
+    if let Some(first) = points.first().cloned() {
+        points.push(first);
+    }
+    Polygon::from(LineString::from(points), vec![])
+}
+
+/// Turns a ring on the sphere into polygons that draw correctly on a flat longitude/latitude map.
+///
+/// Rings crossing the antimeridian are split into a multipolygon. Rings around a pole are
+/// cut at the antimeridian and routed along the map's top or bottom edge through the pole.
+fn planar_geometry(ring: &[Location], pole: Option<f64>) -> Geometry {
+    let n = ring.len();
+    let mut unwrapped = Vec::with_capacity(n);
+    let mut x = ring[0].longitude;
+    for (i, location) in ring.iter().enumerate() {
+        if i > 0 {
+            x += wrap_longitude(location.longitude - ring[i - 1].longitude);
+        }
+        unwrapped.push((x, location.latitude));
+    }
+
+    if let Some(pole) = pole {
+        // Longitude runs monotonically once round the pole, so there's a single antimeridian crossing
+        let cut = (0..n).find(|&i| (ring[(i + 1) % n].longitude - ring[i].longitude).abs() > 180.);
+        if let Some(cut) = cut {
+            let (from, to) = (&ring[cut], &ring[(cut + 1) % n]);
+            let delta = wrap_longitude(to.longitude - from.longitude);
+            let edge = if delta > 0. { 180. } else { -180. };
+            let t = (edge - from.longitude) / delta;
+            let latitude = from.latitude + t * (to.latitude - from.latitude);
+
+            let mut points = vec![(-edge, latitude)];
+            points.extend((1..=n).map(|k| {
+                let location = &ring[(cut + k) % n];
+                (location.longitude, location.latitude)
+            }));
+            points.extend([(edge, latitude), (edge, pole), (-edge, pole)]);
+            return Geometry::Polygon(closed_polygon(points));
+        }
+    }
+
+    let min = unwrapped.iter().map(|p| p.0).fold(f64::INFINITY, f64::min);
+    let max = unwrapped
+        .iter()
+        .map(|p| p.0)
+        .fold(f64::NEG_INFINITY, f64::max);
+    if min >= -180. && max <= 180. {
+        return Geometry::Polygon(closed_polygon(unwrapped));
+    }
+
+    let mut polygons = Vec::new();
+    for shift in [-360., 0., 360.] {
+        let shifted: Vec<(f64, f64)> = unwrapped.iter().map(|&(x, y)| (x + shift, y)).collect();
+        let clipped = clip(&shifted, 180., |x| x <= 180.);
+        let clipped = clip(&clipped, -180., |x| x >= -180.);
+        if clipped.len() >= 3 {
+            polygons.push(closed_polygon(clipped));
+        }
+    }
+    match polygons.len() {
+        1 => Geometry::Polygon(polygons.remove(0)),
+        _ => Geometry::MultiPolygon(polygons),
+    }
+}
+
+impl Circle {
+    /// The circle's outline as `vertices` points, counter-clockwise, following the curvature of the Earth.
+    ///
+    /// Longitudes are normalized, so the ring may jump across the antimeridian; use `to_geometry`
+    /// for something that draws correctly on a flat map.
+    pub fn to_ring(&self, vertices: usize) -> Vec<Location> {
+        let mut ring = Vec::with_capacity(vertices + 1);
+        arc(
+            &self.centre,
+            0.,
+            -360.,
+            central_angle(&self.radius),
+            vertices.max(3),
+            &mut ring,
+        );
+        ring.pop();
+        ring
+    }
+
+    /// The circle as a polygon for flat longitude/latitude maps and formats like GeoJSON.
+    ///
+    /// A circle crossing the antimeridian becomes a multipolygon split along it, and one
+    /// containing a pole runs along the map edge through the pole. Radii reaching both poles aren't supported.
+    pub fn to_geometry(&self, vertices: usize) -> Geometry {
+        let pole = [90., -90.]
+            .into_iter()
+            .find(|&latitude| self.contains(&Location::from(latitude, 0.)));
+        planar_geometry(&self.to_ring(vertices), pole)
+    }
+}
+
+impl LineString {
+    /// A corridor around the line: every point within `distance` of it, with rounded ends and
+    /// outer corners. `cap_vertices` sets how many points each half circle end uses.
+    ///
+    /// The ring is counter-clockwise and follows the curvature of the Earth. Inner corners are
+    /// mitred, so very sharp turns on segments shorter than the distance can self-intersect.
+    pub fn buffer(&self, distance: &Distance, cap_vertices: usize) -> Vec<Location> {
+        let mut points: Vec<Location> = Vec::with_capacity(self.points.len());
+        for point in &self.points {
+            if points.last() != Some(point) {
+                points.push(point.clone());
+            }
+        }
+        let angle = central_angle(distance);
+        let steps = cap_vertices.max(2);
+
+        match points.len() {
+            0 => return Vec::new(),
+            1 => return Circle::from(points[0].clone(), *distance).to_ring(2 * steps),
+            _ => {}
+        }
+
+        let mut ring = vec![destination(
+            &points[0],
+            bearing(&points[0], &points[1]) + 90.,
+            angle,
+        )];
+        let reversed: Vec<Location> = points.iter().rev().cloned().collect();
+        for side in [&points, &reversed] {
+            // The right hand side of the line, then the half circle around its end
+            for i in 0..side.len() - 1 {
+                let (start, end) = (&side[i], &side[i + 1]);
+                let arrival = bearing(end, start) + 180.;
+                if i + 2 == side.len() {
+                    arc(end, arrival + 90., -180., angle, steps, &mut ring);
+                    continue;
+                }
+
+                let turn = wrap_longitude(bearing(end, &side[i + 2]) - arrival);
+                if turn > 0. {
+                    // Turning right, so this side is on the inside of the corner
+                    let miter = angle / cos(turn.to_radians() / 2.).max(0.25);
+                    ring.push(destination(end, arrival + 90. + turn / 2., miter));
+                } else {
+                    let corner_steps = (ceil(-turn / 180. * steps as f64) as usize).max(1);
+                    arc(end, arrival + 90., turn, angle, corner_steps, &mut ring);
+                }
+            }
+        }
+        // The last cap ends where the ring started
+        ring.pop();
+        ring
+    }
+
+    /// The corridor from `buffer` as a polygon for flat longitude/latitude maps, split at the antimeridian if needed.
+    pub fn buffer_geometry(&self, distance: &Distance, cap_vertices: usize) -> Geometry {
+        let ring = self.buffer(distance, cap_vertices);
+        if ring.is_empty() {
+            return Geometry::MultiPolygon(vec![]);
+        }
+        let pole = [90., -90.].into_iter().find(|&latitude| {
+            ring_contains(
+                &ring,
+                &Vector3::from_location(&Location::from(latitude, 0.)),
+            )
+        });
+        planar_geometry(&ring, pole)
+    }
+}
@@ -0,0 +1,326 @@
+use core::fmt;
+use libm::{floor, sqrt};
+
+use crate::location::Location;
+use crate::sphere::Vector3;
+#[cfg(feature = "std")]
+use crate::{
+    circle::Circle,
+    sphere::{angle_to_arc, arcs_cross, central_angle, ring_contains},
+};
+
+/// The finest level, where cells are roughly a centimetre across.
+pub const MAX_CELL_LEVEL: u8 = 30;
+
+const MAX_SIZE: u32 = 1 << MAX_CELL_LEVEL;
+const FACE_BITS: u32 = 61;
+
+// Hilbert curve tables. An orientation combines swapping the i/j axes (1) and inverting them (2).
+const IJ_TO_POS: [[u8; 4]; 4] = [[0, 1, 3, 2], [0, 3, 1, 2], [2, 3, 1, 0], [2, 1, 3, 0]];
+const POS_TO_IJ: [[u8; 4]; 4] = [[0, 1, 3, 2], [0, 2, 3, 1], [3, 2, 0, 1], [3, 1, 0, 2]];
+const POS_TO_ORIENTATION: [u8; 4] = [1, 0, 0, 3];
+
+/// Returns the cube face a point falls on and its `(u, v)` coordinates on that face, each in [-1, 1].
+fn xyz_to_face_uv(p: &Vector3) -> (u8, f64, f64) {
+    let (ax, ay, az) = (p.x.abs(), p.y.abs(), p.z.abs());
+    let mut face = if ax >= ay && ax >= az {
+        0
+    } else if ay >= az {
+        1
+    } else {
+        2
+    };
+    let component = [p.x, p.y, p.z][face as usize];
+    if component < 0. {
+        face += 3;
+    }
+    let (u, v) = match face {
+        0 => (p.y / p.x, p.z / p.x),
+        1 => (-p.x / p.y, p.z / p.y),
+        2 => (-p.x / p.z, -p.y / p.z),
+        3 => (p.z / p.x, p.y / p.x),
+        4 => (p.z / p.y, -p.x / p.y),
+        _ => (-p.y / p.z, -p.x / p.z),
+    };
+    (face, u, v)
+}
+
+fn face_uv_to_xyz(face: u8, u: f64, v: f64) -> Vector3 {
+    match face {
+        0 => Vector3::new(1., u, v),
+        1 => Vector3::new(-u, 1., v),
+        2 => Vector3::new(-u, -v, 1.),
+        3 => Vector3::new(-1., -v, -u),
+        4 => Vector3::new(v, -1., -u),
+        _ => Vector3::new(v, u, -1.),
+    }
+}
+
+/// Warps face coordinates so cells near face corners aren't much smaller than those near the centre.
+fn uv_to_st(u: f64) -> f64 {
+    if u >= 0. {
+        0.5 * sqrt(1. + 3. * u)
+    } else {
+        1. - 0.5 * sqrt(1. - 3. * u)
+    }
+}
+
+fn st_to_uv(s: f64) -> f64 {
+    if s >= 0.5 {
+        (4. * s * s - 1.) / 3.
+    } else {
+        (1. - 4. * (1. - s) * (1. - s)) / 3.
+    }
+}
+
+fn st_to_ij(s: f64) -> u32 {
+    floor(s * MAX_SIZE as f64).clamp(0., (MAX_SIZE - 1) as f64) as u32
+}
+
+/// The point at leaf coordinates `(i, j)` on a face, where `i` and `j` may be fractional.
+fn face_ij_to_xyz(face: u8, i: f64, j: f64) -> Vector3 {
+    let u = st_to_uv(i / MAX_SIZE as f64);
+    let v = st_to_uv(j / MAX_SIZE as f64);
+    face_uv_to_xyz(face, u, v)
+}
+
+/// A cell in a hierarchical grid over the sphere, identified by a 64-bit id.
+///
+/// The sphere is projected onto the six faces of a cube and each face is split
+/// recursively into four children, down to level 30. Ids follow a Hilbert curve,
+/// so nearby cells usually have nearby ids, and a parent's id range contains its children.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CellId(u64);
+
+impl CellId {
+    /// Wraps a raw id, returning `None` if it doesn't identify a valid cell.
+    pub fn from_raw(id: u64) -> Option<Self> {
+        let cell = CellId(id);
+        let valid = id >> FACE_BITS < 6 && cell.lsb() & 0x1555_5555_5555_5555 != 0;
+        valid.then_some(cell)
+    }
+
+    pub fn id(&self) -> u64 {
+        self.0
+    }
+
+    /// The level 0 cell covering a whole cube face, numbered 0 to 5. Returns `None` for other faces.
+    pub fn from_face(face: u8) -> Option<Self> {
+        (face < 6).then(|| CellId(((face as u64) << FACE_BITS) + (1 << 60)))
+    }
+
+    fn from_face_ij(face: u8, i: u32, j: u32) -> Self {
+        let mut orientation = face & 1;
+        let mut bits = 0u64;
+        for k in (0..MAX_CELL_LEVEL).rev() {
+            let ij = (((i >> k) & 1) << 1 | ((j >> k) & 1)) as usize;
+            let pos = IJ_TO_POS[orientation as usize][ij];
+            bits = bits << 2 | pos as u64;
+            orientation ^= POS_TO_ORIENTATION[pos as usize];
+        }
+        CellId((face as u64) << FACE_BITS | bits << 1 | 1)
+    }
+
+    fn from_vector(p: &Vector3, level: u8) -> Self {
+        let (face, u, v) = xyz_to_face_uv(p);
+        Self::from_face_ij(face, st_to_ij(uv_to_st(u)), st_to_ij(uv_to_st(v))).parent(level)
+    }
+
+    /// The cell at `level` containing `location`. Levels above 30 are treated as 30.
+    pub fn from_location(location: &Location, level: u8) -> Self {
+        Self::from_vector(&Vector3::from_location(location), level)
+    }
+
+    fn lsb(&self) -> u64 {
+        self.0 & self.0.wrapping_neg()
+    }
+
+    fn lsb_for_level(level: u8) -> u64 {
+        1 << (2 * (MAX_CELL_LEVEL - level.min(MAX_CELL_LEVEL)) as u64)
+    }
+
+    pub fn face(&self) -> u8 {
+        (self.0 >> FACE_BITS) as u8
+    }
+
+    pub fn level(&self) -> u8 {
+        MAX_CELL_LEVEL - (self.0.trailing_zeros() / 2) as u8
+    }
+
+    pub fn is_leaf(&self) -> bool {
+        self.0 & 1 == 1
+    }
+
+    /// The cell's face and the leaf coordinates of its lower corner.
+    fn face_ij(&self) -> (u8, u32, u32) {
+        let level = self.level();
+        let mut orientation = self.face() & 1;
+        let (mut i, mut j) = (0u32, 0u32);
+        for k in 0..level {
+            let pos = (self.0 >> (FACE_BITS - 2 - 2 * k as u32)) & 3;
+            let ij = POS_TO_IJ[orientation as usize][pos as usize];
+            i = i << 1 | (ij >> 1) as u32;
+            j = j << 1 | (ij & 1) as u32;
+            orientation ^= POS_TO_ORIENTATION[pos as usize];
+        }
+        let shift = MAX_CELL_LEVEL - level;
+        (self.face(), i << shift, j << shift)
+    }
+
+    fn size_ij(&self) -> u32 {
+        1 << (MAX_CELL_LEVEL - self.level())
+    }
+
+    /// The ancestor at `level`, or the cell itself if it's already at or above that level.
+    pub fn parent(&self, level: u8) -> Self {
+        if level >= self.level() {
+            return *self;
+        }
+        let lsb = Self::lsb_for_level(level);
+        CellId((self.0 & lsb.wrapping_neg()) | lsb)
+    }
+
+    /// The four children in Hilbert curve order, or `None` for a leaf cell.
+    pub fn children(&self) -> Option<[CellId; 4]> {
+        if self.is_leaf() {
+            return None;
+        }
+        let child_lsb = self.lsb() >> 2;
+        let first = self.0 - self.lsb() + child_lsb;
+        Some([0, 1, 2, 3].map(|k| CellId(first + 2 * k * child_lsb)))
+    }
+
+    /// Whether `other` is this cell or one of its descendants.
+    pub fn contains(&self, other: &CellId) -> bool {
+        let lsb = self.lsb();
+        other.0 >= self.0 - (lsb - 1) && other.0 <= self.0 + (lsb - 1)
+    }
+
+    fn centre_vector(&self) -> Vector3 {
+        let (face, i, j) = self.face_ij();
+        let half = self.size_ij() as f64 / 2.;
+        face_ij_to_xyz(face, i as f64 + half, j as f64 + half)
+    }
+
+    fn vertex_vectors(&self) -> [Vector3; 4] {
+        let (face, i, j) = self.face_ij();
+        let (i0, j0) = (i as f64, j as f64);
+        let size = self.size_ij() as f64;
+        [(0., 0.), (1., 0.), (1., 1.), (0., 1.)]
+            .map(|(di, dj)| face_ij_to_xyz(face, i0 + di * size, j0 + dj * size))
+    }
+
+    pub fn centre(&self) -> Location {
+        self.centre_vector().to_location()
+    }
+
+    /// The four corners, counter-clockwise. Cell edges are great circle arcs between them.
+    pub fn boundary(&self) -> [Location; 4] {
+        self.vertex_vectors().map(|vertex| vertex.to_location())
+    }
+
+    /// The cells at the same level sharing an edge with this one, ordered below, right, above and left
+    /// in the face's own coordinates. Neighbours across cube edges are on the adjacent face.
+    pub fn edge_neighbours(&self) -> [CellId; 4] {
+        let (face, i, j) = self.face_ij();
+        let size = self.size_ij() as f64;
+        let (i, j) = (i as f64, j as f64);
+        let level = self.level();
+        // Step half a leaf cell over each edge, re-projecting onto another face when off this one
+        [
+            (i + size / 2., j - 0.5),
+            (i + size + 0.5, j + size / 2.),
+            (i + size / 2., j + size + 0.5),
+            (i - 0.5, j + size / 2.),
+        ]
+        .map(|(ni, nj)| Self::from_vector(&face_ij_to_xyz(face, ni, nj), level))
+    }
+}
+
+#[cfg(feature = "std")]
+impl CellId {
+    /// Whether `point` is inside the cell, using its great circle edges.
+    fn contains_vector(&self, point: &Vector3) -> bool {
+        let vertices = self.vertex_vectors();
+        (0..4).all(|k| vertices[k].cross(&vertices[(k + 1) % 4]).dot(point) >= 0.)
+    }
+
+    /// Finds every cell at `level` intersecting a region, subdividing from the six faces and only
+    /// descending into cells that pass `intersects`.
+    fn cover(
+        level: u8,
+        max_cells: usize,
+        intersects: impl Fn(&CellId) -> bool,
+    ) -> Option<Vec<CellId>> {
+        let mut cells = Vec::new();
+        let mut pending: Vec<CellId> = (0..6).filter_map(CellId::from_face).collect();
+        while let Some(cell) = pending.pop() {
+            if !intersects(&cell) {
+                continue;
+            }
+            match cell.children() {
+                Some(children) if cell.level() < level => pending.extend(children),
+                _ if cells.len() == max_cells => return None,
+                _ => cells.push(cell),
+            }
+        }
+        cells.sort();
+        Some(cells)
+    }
+
+    /// The cells at `level` that intersect a circle, or `None` if there are more than `max_cells`.
+    pub fn cover_circle(circle: &Circle, level: u8, max_cells: usize) -> Option<Vec<CellId>> {
+        let centre = Vector3::from_location(&circle.centre);
+        let radius = central_angle(&circle.radius);
+        Self::cover(level, max_cells, |cell| {
+            let vertices = cell.vertex_vectors();
+            cell.contains_vector(&centre)
+                || (0..4)
+                    .any(|k| angle_to_arc(&centre, &vertices[k], &vertices[(k + 1) % 4]) <= radius)
+        })
+    }
+
+    /// The cells at `level` that intersect a polygon whose vertices are joined by great circle arcs,
+    /// or `None` if there are more than `max_cells`. The ring may be open or closed and in either
+    /// winding order.
+    pub fn cover_polygon(ring: &[Location], level: u8, max_cells: usize) -> Option<Vec<CellId>> {
+        if ring.len() < 3 {
+            return Some(Vec::new());
+        }
+        let points: Vec<Vector3> = ring.iter().map(Vector3::from_location).collect();
+        Self::cover(level, max_cells, |cell| {
+            let vertices = cell.vertex_vectors();
+            vertices.iter().any(|vertex| ring_contains(ring, vertex))
+                || points.iter().any(|point| cell.contains_vector(point))
+                || (0..4).any(|k| {
+                    let (a, b) = (&vertices[k], &vertices[(k + 1) % 4]);
+                    (0..points.len())
+                        .any(|n| arcs_cross(a, b, &points[n], &points[(n + 1) % points.len()]))
+                })
+        })
+    }
+}
+
+/// Formats the id as a compact hex token with trailing zeros removed.
+impl fmt::Display for CellId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let zeros = self.0.trailing_zeros() / 4;
+        write!(
+            f,
+            "{:0width$x}",
+            self.0 >> (4 * zeros),
+            width = 16 - zeros as usize
+        )
+    }
+}
+
+impl CellId {
+    /// Parses a token produced by `Display`.
+    pub fn from_token(token: &str) -> Option<Self> {
+        if token.is_empty() || token.len() > 16 {
+            return None;
+        }
+        let value = u64::from_str_radix(token, 16).ok()?;
+        Self::from_raw(value << (4 * (16 - token.len())))
+    }
+}
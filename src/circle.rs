@@ -0,0 +1,21 @@
+use crate::location::Location;
+use crate::measurement::Distance;
+use crate::sphere::{central_angle, Vector3};
+
+/// A spherical cap: every location within `radius` of `centre` along the Earth's surface.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Circle {
+    pub centre: Location,
+    pub radius: Distance,
+}
+
+impl Circle {
+    pub fn from(centre: Location, radius: Distance) -> Self {
+        Self { centre, radius }
+    }
+
+    pub fn contains(&self, location: &Location) -> bool {
+        let centre = Vector3::from_location(&self.centre);
+        centre.angle(&Vector3::from_location(location)) <= central_angle(&self.radius)
+    }
+}
@@ -0,0 +1,283 @@
+use libm::{cos, pow};
+
+use crate::location::{Location, RADIUS_OF_EARTH};
+use crate::measurement::Distance;
+use crate::speed::Speed;
+use crate::sphere::wrap_longitude;
+use crate::track::{Track, TrackPoint};
+
+/// A flat approximation in meters east and north of an origin, accurate over the tens of
+/// kilometers a track usually spans.
+struct Local {
+    origin: Location,
+    meters_per_degree: f64,
+    cos_latitude: f64,
+}
+
+impl Local {
+    fn new(origin: &Location) -> Self {
+        Self {
+            origin: origin.clone(),
+            meters_per_degree: RADIUS_OF_EARTH.meters().to_radians(),
+            cos_latitude: cos(origin.latitude.to_radians()).max(1e-6),
+        }
+    }
+
+    fn project(&self, location: &Location) -> (f64, f64) {
+        let east = wrap_longitude(location.longitude - self.origin.longitude)
+            * self.meters_per_degree
+            * self.cos_latitude;
+        let north = (location.latitude - self.origin.latitude) * self.meters_per_degree;
+        (east, north)
+    }
+
+    fn unproject(&self, (east, north): (f64, f64)) -> Location {
+        let latitude = self.origin.latitude + north / self.meters_per_degree;
+        let longitude = self.origin.longitude + east / (self.meters_per_degree * self.cos_latitude);
+        Location::from(latitude.clamp(-90., 90.), wrap_longitude(longitude))
+    }
+}
+
+/// Weights for a Savitzky–Golay filter: the least squares polynomial of `degree` fitted
+/// to offsets `-half..=half`, evaluated at zero.
+fn savitzky_golay_weights(half: usize, degree: usize) -> Vec<f64> {
+    let half = half as i64;
+    let size = degree + 1;
+    // The normal equations AᵀA, where A[i][j] = offset(i)^j
+    let mut matrix = vec![vec![0.; size + 1]; size];
+    for (row, entries) in matrix.iter_mut().enumerate() {
+        for (column, entry) in entries.iter_mut().take(size).enumerate() {
+            *entry = (-half..=half)
+                .map(|offset| pow(offset as f64, (row + column) as f64))
+                .sum();
+        }
+    }
+    // Solve AᵀA c = e₀ for the first row of the inverse, by Gauss-Jordan elimination
+    matrix[0][size] = 1.;
+    for pivot in 0..size {
+        let best = (pivot..size)
+            .max_by(|&a, &b| matrix[a][pivot].abs().total_cmp(&matrix[b][pivot].abs()))
+            .unwrap_or(pivot);
+        matrix.swap(pivot, best);
+        let divisor = matrix[pivot][pivot];
+        for entry in matrix[pivot].iter_mut() {
+            *entry /= divisor;
+        }
+        let pivot_row = matrix[pivot].clone();
+        for (row, entries) in matrix.iter_mut().enumerate() {
+            if row != pivot {
+                let factor = entries[pivot];
+                for (entry, value) in entries.iter_mut().zip(&pivot_row).skip(pivot) {
+                    *entry -= factor * value;
+                }
+            }
+        }
+    }
+    let coefficients: Vec<f64> = matrix.iter().map(|row| row[size]).collect();
+    (-half..=half)
+        .map(|offset| {
+            coefficients
+                .iter()
+                .enumerate()
+                .map(|(power, c)| c * pow(offset as f64, power as f64))
+                .sum()
+        })
+        .collect()
+}
+
+impl Track {
+    /// Replaces each location and elevation with a weighted sum over a centred window of
+    /// points. Near the ends the window shrinks to stay centred. `weights(half)` gives the
+    /// `2 * half + 1` weights for a window.
+    fn smooth(&self, window: usize, weights: impl Fn(usize) -> Vec<f64>) -> Track {
+        let n = self.points.len();
+        let (first, half) = match self.points.first() {
+            Some(first) if window >= 3 => (first, (window - 1) / 2),
+            _ => return self.clone(),
+        };
+        let local = Local::new(&first.location);
+        let projected: Vec<(f64, f64)> = self
+            .points
+            .iter()
+            .map(|point| local.project(&point.location))
+            .collect();
+
+        let mut points = self.points.clone();
+        for (i, point) in points.iter_mut().enumerate() {
+            let half = half.min(i).min(n - 1 - i);
+            if half == 0 {
+                continue;
+            }
+            let weights = weights(half);
+            let range = i - half..=i + half;
+            let (mut east, mut north) = (0., 0.);
+            for (weight, &(x, y)) in weights.iter().zip(&projected[range.clone()]) {
+                east += weight * x;
+                north += weight * y;
+            }
+            point.location = local.unproject((east, north));
+
+            let elevations: Option<Vec<f64>> = self.points[range]
+                .iter()
+                .map(|point| point.elevation)
+                .collect();
+            if let Some(elevations) = elevations {
+                point.elevation = Some(weights.iter().zip(&elevations).map(|(w, e)| w * e).sum());
+            }
+        }
+        Track {
+            points,
+            moving_threshold: self.moving_threshold,
+        }
+    }
+
+    /// Drops points that could only be reached faster than `max_speed`, like GPS jumps.
+    ///
+    /// Each point is compared with the last one kept, so a jump and the return from it are
+    /// both caught. The first point is trusted, and points at the same time as the last one
+    /// kept are dropped unless they're at the same place.
+    pub fn remove_outliers(&self, max_speed: Speed) -> Track {
+        let limit = max_speed.meters_per_second();
+        let mut points: Vec<TrackPoint> = Vec::with_capacity(self.points.len());
+        for point in &self.points {
+            let keep = match points.last() {
+                None => true,
+                Some(last) => {
+                    let meters = last.location.distance(&point.location).meters();
+                    let seconds = point.time.seconds_since(&last.time);
+                    if seconds > 0. {
+                        meters / seconds <= limit
+                    } else {
+                        seconds == 0. && meters == 0.
+                    }
+                }
+            };
+            if keep {
+                points.push(point.clone());
+            }
+        }
+        Track {
+            points,
+            moving_threshold: self.moving_threshold,
+        }
+    }
+
+    /// Collapses runs of points within `radius` of where the run started, such as duplicates
+    /// or GPS drift while stopped, down to their first and last points.
+    ///
+    /// Keeping the last point preserves how long the stop lasted. Use a zero radius to only
+    /// collapse repeated locations.
+    pub fn collapse_stationary(&self, radius: &Distance) -> Track {
+        let limit = radius.meters();
+        let mut points: Vec<TrackPoint> = Vec::with_capacity(self.points.len());
+        let mut anchor: Option<&TrackPoint> = None;
+        let mut pending: Option<&TrackPoint> = None;
+        for point in &self.points {
+            if let Some(anchor) = anchor {
+                if anchor.location.distance(&point.location).meters() <= limit {
+                    pending = Some(point);
+                    continue;
+                }
+            }
+            if let Some(pending) = pending.take() {
+                points.push(pending.clone());
+            }
+            points.push(point.clone());
+            anchor = Some(point);
+        }
+        if let Some(pending) = pending {
+            points.push(pending.clone());
+        }
+        Track {
+            points,
+            moving_threshold: self.moving_threshold,
+        }
+    }
+
+    /// Smooths the track with a centred moving average over `window` points, rounded down
+    /// to an odd number. Elevations are averaged too where every point in the window has one.
+    pub fn moving_average(&self, window: usize) -> Track {
+        self.smooth(window, |half| {
+            vec![1. / (2 * half + 1) as f64; 2 * half + 1]
+        })
+    }
+
+    /// Smooths the track with a Savitzky–Golay filter, fitting a polynomial of `degree` over
+    /// `window` points (rounded down to odd). It keeps the shape of corners better than a
+    /// moving average. Points are treated as evenly spaced, which suits a steady logging rate.
+    pub fn savitzky_golay(&self, window: usize, degree: usize) -> Track {
+        self.smooth(window, |half| {
+            savitzky_golay_weights(half, degree.min(2 * half))
+        })
+    }
+
+    /// Filters positions with a constant velocity Kalman filter, running east and north separately.
+    ///
+    /// `accuracy` is the standard deviation of GPS error, and `acceleration` how much the speed
+    /// is expected to change, in m/s². Larger accelerations follow the fixes more closely, and
+    /// smaller ones smooth more. Times come from the timestamps, so gaps are handled.
+    pub fn kalman(&self, accuracy: &Distance, acceleration: f64) -> Track {
+        let first = match self.points.first() {
+            Some(first) => first,
+            None => return self.clone(),
+        };
+        let local = Local::new(&first.location);
+        let measurement_variance = pow(accuracy.meters(), 2.);
+        let process_variance = pow(acceleration, 2.);
+
+        /// Position, velocity, and their covariance along one axis.
+        struct Axis {
+            position: f64,
+            velocity: f64,
+            covariance: [[f64; 2]; 2],
+        }
+
+        impl Axis {
+            fn step(&mut self, measured: f64, dt: f64, process: f64, measurement: f64) -> f64 {
+                // Predict
+                self.position += self.velocity * dt;
+                let [[p00, p01], [p10, p11]] = self.covariance;
+                let (dt2, dt3, dt4) = (dt * dt, dt * dt * dt, dt * dt * dt * dt);
+                let p00 = p00 + dt * (p10 + p01) + dt2 * p11 + process * dt4 / 4.;
+                let p01 = p01 + dt * p11 + process * dt3 / 2.;
+                let p10 = p10 + dt * p11 + process * dt3 / 2.;
+                let p11 = p11 + process * dt2;
+
+                // Update with the measured position
+                let innovation = measured - self.position;
+                let total = p00 + measurement;
+                let (gain_position, gain_velocity) = (p00 / total, p10 / total);
+                self.position += gain_position * innovation;
+                self.velocity += gain_velocity * innovation;
+                self.covariance = [
+                    [(1. - gain_position) * p00, (1. - gain_position) * p01],
+                    [p10 - gain_velocity * p00, p11 - gain_velocity * p01],
+                ];
+                self.position
+            }
+        }
+
+        let start = |position: f64| Axis {
+            position,
+            velocity: 0.,
+            covariance: [[measurement_variance, 0.], [0., 100.]],
+        };
+        let (x, y) = local.project(&first.location);
+        let (mut east, mut north) = (start(x), start(y));
+
+        let mut points = self.points.clone();
+        for i in 1..points.len() {
+            let dt = points[i].time.seconds_since(&points[i - 1].time).max(0.);
+            let (x, y) = local.project(&points[i].location);
+            let filtered = (
+                east.step(x, dt, process_variance, measurement_variance),
+                north.step(y, dt, process_variance, measurement_variance),
+            );
+            points[i].location = local.unproject(filtered);
+        }
+        Track {
+            points,
+            moving_threshold: self.moving_threshold,
+        }
+    }
+}
@@ -0,0 +1,122 @@
+use core::f64::consts::PI;
+use libm::{cos, fmod, log, tan};
+use std::collections::HashMap;
+
+use crate::ball_tree::BallTree;
+use crate::location::Location;
+use crate::measurement::Distance;
+use crate::sphere::spherical_mean;
+
+/// A group of locations with the mean of their positions.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cluster {
+    pub centre: Location,
+    /// Indices into the slice that was clustered, in ascending order.
+    pub members: Vec<usize>,
+}
+
+impl Cluster {
+    fn from_members(locations: &[Location], members: Vec<usize>) -> Self {
+        let centre = spherical_mean(members.iter().map(|&i| &locations[i])).unwrap_or_default();
+        Self { centre, members }
+    }
+}
+
+/// The result of `dbscan`: the clusters found and the indices of points that belong to none.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Clustering {
+    pub clusters: Vec<Cluster>,
+    pub noise: Vec<usize>,
+}
+
+/// Density based clustering. A point with at least `min_points` locations (itself included)
+/// within `epsilon` starts or extends a cluster, and points reachable from it join that cluster.
+///
+/// Distances are great circle distances, so clusters can span the antimeridian.
+pub fn dbscan(locations: &[Location], epsilon: &Distance, min_points: usize) -> Clustering {
+    let tree: BallTree<usize> = locations.iter().cloned().zip(0..).collect();
+    let neighbours = |i: usize| -> Vec<usize> {
+        tree.within(&locations[i], epsilon)
+            .into_iter()
+            .map(|(&j, _)| j)
+            .collect()
+    };
+
+    let mut labels: Vec<Option<usize>> = vec![None; locations.len()];
+    let mut visited = vec![false; locations.len()];
+    let mut clusters: Vec<Vec<usize>> = Vec::new();
+
+    for start in 0..locations.len() {
+        if visited[start] {
+            continue;
+        }
+        visited[start] = true;
+        let found = neighbours(start);
+        if found.len() < min_points {
+            continue;
+        }
+
+        let cluster = clusters.len();
+        clusters.push(Vec::new());
+        labels[start] = Some(cluster);
+        let mut pending = found;
+        while let Some(point) = pending.pop() {
+            if labels[point].is_none() {
+                labels[point] = Some(cluster);
+            }
+            if visited[point] {
+                continue;
+            }
+            visited[point] = true;
+            let found = neighbours(point);
+            if found.len() >= min_points {
+                pending.extend(found);
+            }
+        }
+    }
+
+    let mut noise = Vec::new();
+    for (i, label) in labels.iter().enumerate() {
+        match label {
+            Some(cluster) => clusters[*cluster].push(i),
+            None => noise.push(i),
+        }
+    }
+    Clustering {
+        clusters: clusters
+            .into_iter()
+            .map(|members| Cluster::from_members(locations, members))
+            .collect(),
+        noise,
+    }
+}
+
+/// Web Mercator pixel coordinates at `zoom` for 256 pixel tiles.
+fn project(location: &Location, zoom: u8) -> (f64, f64) {
+    // Mercator stretches to infinity at the poles, so clamp to the usual map limit
+    let latitude = location.latitude.clamp(-85.051_128_78, 85.051_128_78) * PI / 180.;
+    let world = 256. * (1u64 << zoom.min(32)) as f64;
+    let x = (location.longitude + 180.) / 360. * world;
+    let y = (1. - log(tan(latitude) + 1. / cos(latitude)) / PI) / 2. * world;
+    (fmod(fmod(x, world) + world, world), y)
+}
+
+/// Groups locations into square grid cells `cell_pixels` wide on a Web Mercator map at `zoom`,
+/// like map marker clustering. Each cluster's centre is the mean of its members, not the grid cell's centre.
+///
+/// Clusters are ordered by their first member.
+pub fn grid_clusters(locations: &[Location], zoom: u8, cell_pixels: f64) -> Vec<Cluster> {
+    let mut cells: HashMap<(i64, i64), Vec<usize>> = HashMap::new();
+    for (i, location) in locations.iter().enumerate() {
+        let (x, y) = project(location, zoom);
+        let key = ((x / cell_pixels) as i64, (y / cell_pixels) as i64);
+        cells.entry(key).or_default().push(i);
+    }
+
+    let mut clusters: Vec<Cluster> = cells
+        .into_values()
+        .map(|members| Cluster::from_members(locations, members))
+        .collect();
+    clusters.sort_by_key(|cluster| cluster.members[0]);
+    clusters
+}
@@ -0,0 +1,408 @@
+use core::fmt;
+use std::io::{self, BufRead, Write};
+
+use crate::dms::{parse_dms, Axis, Dms};
+use crate::location::Location;
+
+#[derive(Debug)]
+pub enum CsvError {
+    Io(io::Error),
+    /// A named column wasn't found in the header row.
+    MissingColumn {
+        name: String,
+    },
+    /// A row didn't have enough fields for the latitude or longitude column.
+    MissingField {
+        line: usize,
+        column: usize,
+    },
+    /// A latitude or longitude field couldn't be parsed.
+    InvalidCoordinate {
+        line: usize,
+        column: usize,
+        value: String,
+    },
+    /// A quoted field was still open at the end of the input.
+    UnterminatedQuote {
+        line: usize,
+    },
+}
+
+impl fmt::Display for CsvError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CsvError::Io(error) => write!(f, "CSV I/O error: {}", error),
+            CsvError::MissingColumn { name } => write!(f, "CSV column {:?} not found", name),
+            CsvError::MissingField { line, column } => {
+                write!(f, "line {} has no field for column {}", line, column)
+            }
+            CsvError::InvalidCoordinate {
+                line,
+                column,
+                value,
+            } => write!(
+                f,
+                "invalid coordinate {:?} on line {} column {}",
+                value, line, column
+            ),
+            CsvError::UnterminatedQuote { line } => {
+                write!(f, "unterminated quoted field starting on line {}", line)
+            }
+        }
+    }
+}
+
+impl std::error::Error for CsvError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            CsvError::Io(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for CsvError {
+    fn from(error: io::Error) -> Self {
+        CsvError::Io(error)
+    }
+}
+
+/// Selects a column either by its zero based index or by its header name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CsvColumn {
+    Index(usize),
+    /// Matched against the header row, ignoring ASCII case.
+    Name(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CoordinateFormat {
+    /// Signed decimal degrees, e.g. `-111.7656248`.
+    Decimal,
+    /// Degrees, minutes and seconds as understood by `parse_dms`, e.g. `111°45'56.2"W`.
+    Dms,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InvalidRows {
+    /// Rows that can't be parsed are skipped and counted.
+    Skip,
+    /// Rows that can't be parsed are returned as errors and reading continues.
+    Report,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CsvOptions {
+    pub latitude: CsvColumn,
+    pub longitude: CsvColumn,
+    pub delimiter: char,
+    /// Whether the first row holds column names. Required when selecting columns by name.
+    pub has_header: bool,
+    pub format: CoordinateFormat,
+    pub invalid_rows: InvalidRows,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        Self {
+            latitude: CsvColumn::Name("latitude".into()),
+            longitude: CsvColumn::Name("longitude".into()),
+            delimiter: ',',
+            has_header: true,
+            format: CoordinateFormat::Decimal,
+            invalid_rows: InvalidRows::Report,
+        }
+    }
+}
+
+/// A parsed row: the location plus every other field in column order.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CsvRecord {
+    /// The line the row started on, counting from 1.
+    pub line: usize,
+    pub location: Location,
+    pub extra: Vec<String>,
+}
+
+/// Splits one record into fields. Returns `None` if a quoted field is still open.
+fn split_fields(record: &str, delimiter: char) -> Option<Vec<String>> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = record.chars().peekable();
+
+    while let Some(character) = chars.next() {
+        if quoted {
+            match character {
+                '"' if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    field.push('"');
+                }
+                '"' => quoted = false,
+                _ => field.push(character),
+            }
+        } else if character == '"' && field.is_empty() {
+            quoted = true;
+        } else if character == delimiter {
+            fields.push(core::mem::take(&mut field));
+        } else {
+            field.push(character);
+        }
+    }
+
+    if quoted {
+        return None;
+    }
+    fields.push(field);
+    Some(fields)
+}
+
+/// A streaming reader yielding one `CsvRecord` per row.
+pub struct CsvReader<R> {
+    reader: R,
+    options: CsvOptions,
+    columns: Option<(usize, usize)>,
+    headers: Vec<String>,
+    line: usize,
+    skipped: usize,
+    /// Set once the columns couldn't be resolved, after which the reader yields nothing.
+    failed: bool,
+}
+
+impl<R: BufRead> CsvReader<R> {
+    pub fn new(reader: R, options: CsvOptions) -> Self {
+        Self {
+            reader,
+            options,
+            columns: None,
+            headers: Vec::new(),
+            line: 0,
+            skipped: 0,
+            failed: false,
+        }
+    }
+
+    /// The header names of the extra fields, once the header row has been read.
+    pub fn extra_headers(&self) -> Vec<&str> {
+        match self.columns {
+            Some((lat, lng)) => self
+                .headers
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| *i != lat && *i != lng)
+                .map(|(_, name)| name.as_str())
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// The number of rows skipped so far with `InvalidRows::Skip`.
+    pub fn skipped_rows(&self) -> usize {
+        self.skipped
+    }
+
+    /// Reads the next record, joining physical lines while a quoted field is open.
+    fn read_record(&mut self) -> Result<Option<(usize, Vec<String>)>, CsvError> {
+        let mut buffer = String::new();
+        let start = self.line + 1;
+        loop {
+            let read = self.reader.read_line(&mut buffer)?;
+            if read == 0 {
+                if buffer.is_empty() {
+                    return Ok(None);
+                }
+                return Err(CsvError::UnterminatedQuote { line: start });
+            }
+            self.line += 1;
+
+            let record = buffer.trim_end_matches(['\r', '\n']);
+            if let Some(fields) = split_fields(record, self.options.delimiter) {
+                return Ok(Some((start, fields)));
+            }
+        }
+    }
+
+    fn resolve(&self, column: &CsvColumn) -> Result<usize, CsvError> {
+        match column {
+            CsvColumn::Index(index) => Ok(*index),
+            CsvColumn::Name(name) => self
+                .headers
+                .iter()
+                .position(|header| header.trim().eq_ignore_ascii_case(name))
+                .ok_or_else(|| CsvError::MissingColumn { name: name.clone() }),
+        }
+    }
+
+    fn columns(&mut self) -> Result<(usize, usize), CsvError> {
+        if let Some(columns) = self.columns {
+            return Ok(columns);
+        }
+        if self.options.has_header {
+            if let Some((_, headers)) = self.read_record()? {
+                self.headers = headers;
+            }
+        }
+        let columns = (
+            self.resolve(&self.options.latitude)?,
+            self.resolve(&self.options.longitude)?,
+        );
+        self.columns = Some(columns);
+        Ok(columns)
+    }
+
+    fn parse_coordinate(&self, line: usize, column: usize, value: &str) -> Result<f64, CsvError> {
+        let invalid = || CsvError::InvalidCoordinate {
+            line,
+            column,
+            value: value.into(),
+        };
+        match self.options.format {
+            CoordinateFormat::Decimal => value.trim().parse().map_err(|_| invalid()),
+            CoordinateFormat::Dms => parse_dms(value).map_err(|_| invalid()),
+        }
+    }
+
+    fn parse_record(
+        &self,
+        line: usize,
+        mut fields: Vec<String>,
+        (lat, lng): (usize, usize),
+    ) -> Result<CsvRecord, CsvError> {
+        let field = |column: usize| {
+            fields
+                .get(column)
+                .ok_or(CsvError::MissingField { line, column })
+        };
+        let latitude = self.parse_coordinate(line, lat, field(lat)?)?;
+        let longitude = self.parse_coordinate(line, lng, field(lng)?)?;
+
+        let mut index = 0;
+        fields.retain(|_| {
+            let keep = index != lat && index != lng;
+            index += 1;
+            keep
+        });
+
+        Ok(CsvRecord {
+            line,
+            location: Location::from(latitude, longitude),
+            extra: fields,
+        })
+    }
+}
+
+impl<R: BufRead> Iterator for CsvReader<R> {
+    type Item = Result<CsvRecord, CsvError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+        let columns = match self.columns() {
+            Ok(columns) => columns,
+            Err(error) => {
+                self.failed = true;
+                return Some(Err(error));
+            }
+        };
+
+        loop {
+            let (line, fields) = match self.read_record() {
+                Ok(Some(record)) => record,
+                Ok(None) => return None,
+                Err(error) => return Some(Err(error)),
+            };
+            if fields.len() == 1 && fields[0].trim().is_empty() {
+                continue;
+            }
+
+            match self.parse_record(line, fields, columns) {
+                Ok(record) => return Some(Ok(record)),
+                Err(_) if self.options.invalid_rows == InvalidRows::Skip => self.skipped += 1,
+                Err(error) => return Some(Err(error)),
+            }
+        }
+    }
+}
+
+/// Writes rows with the latitude and longitude in the first two columns, followed by any extra fields.
+pub struct CsvWriter<W> {
+    writer: W,
+    delimiter: char,
+    format: CoordinateFormat,
+}
+
+impl<W: Write> CsvWriter<W> {
+    pub fn new(writer: W, delimiter: char, format: CoordinateFormat) -> Self {
+        Self {
+            writer,
+            delimiter,
+            format,
+        }
+    }
+
+    fn write_field(&mut self, field: &str) -> io::Result<()> {
+        let needs_quotes = field.contains(self.delimiter)
+            || field.contains('"')
+            || field.contains('\n')
+            || field.contains('\r');
+        if needs_quotes {
+            write!(self.writer, "\"{}\"", field.replace('"', "\"\""))
+        } else {
+            write!(self.writer, "{}", field)
+        }
+    }
+
+    fn write_row<'a>(&mut self, fields: impl Iterator<Item = &'a str>) -> io::Result<()> {
+        for (i, field) in fields.enumerate() {
+            if i > 0 {
+                write!(self.writer, "{}", self.delimiter)?;
+            }
+            self.write_field(field)?;
+        }
+        writeln!(self.writer)
+    }
+
+    pub fn write_header(
+        &mut self,
+        latitude: &str,
+        longitude: &str,
+        extra: &[&str],
+    ) -> io::Result<()> {
+        self.write_row(
+            [latitude, longitude]
+                .into_iter()
+                .chain(extra.iter().copied()),
+        )
+    }
+
+    pub fn write_record<S: AsRef<str>>(
+        &mut self,
+        location: &Location,
+        extra: &[S],
+    ) -> io::Result<()> {
+        let (latitude, longitude) = match self.format {
+            CoordinateFormat::Decimal => (
+                location.latitude.to_string(),
+                location.longitude.to_string(),
+            ),
+            CoordinateFormat::Dms => (
+                Dms::from_decimal(location.latitude, Axis::Latitude).to_string(),
+                Dms::from_decimal(location.longitude, Axis::Longitude).to_string(),
+            ),
+        };
+        self.write_row(
+            [latitude.as_str(), longitude.as_str()]
+                .into_iter()
+                .chain(extra.iter().map(AsRef::as_ref)),
+        )
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
@@ -0,0 +1,182 @@
+use core::fmt;
+use libm::{fabs, round, trunc};
+
+/// An error produced while parsing a degrees, minutes and seconds coordinate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DmsError {
+    /// The input had no numbers in it.
+    Empty,
+    /// A character that isn't part of a coordinate was found.
+    UnexpectedCharacter { character: char, position: usize },
+    /// There were more than three numbers, or a number couldn't be parsed.
+    InvalidNumber { position: usize },
+    /// Minutes or seconds were 60 or more.
+    OutOfRange,
+    /// The input had both a hemisphere letter and a minus sign, or more than one letter.
+    ConflictingSign,
+}
+
+impl fmt::Display for DmsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DmsError::Empty => write!(f, "coordinate is empty"),
+            DmsError::UnexpectedCharacter {
+                character,
+                position,
+            } => write!(
+                f,
+                "unexpected character {:?} in coordinate at position {}",
+                character, position
+            ),
+            DmsError::InvalidNumber { position } => {
+                write!(f, "invalid number in coordinate at position {}", position)
+            }
+            DmsError::OutOfRange => write!(f, "minutes and seconds must be less than 60"),
+            DmsError::ConflictingSign => write!(f, "coordinate has conflicting signs"),
+        }
+    }
+}
+
+impl core::error::Error for DmsError {}
+
+/// Which hemisphere letters a coordinate uses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Axis {
+    Latitude,
+    Longitude,
+}
+
+/// Parses a coordinate in decimal degrees, degrees and decimal minutes, or
+/// degrees, minutes and seconds, returning signed decimal degrees.
+///
+/// Accepts forms like `-111.7656`, `40° 47.3127' N`, `40°47'18.76"N`,
+/// `N 40 47 18.76` and `111:45:56.2W`. South and west are negative.
+pub fn parse_dms(input: &str) -> Result<f64, DmsError> {
+    let mut parts = [0f64; 3];
+    let mut count = 0;
+    let mut negative = false;
+    let mut hemisphere: Option<bool> = None;
+
+    let mut chars = input.char_indices().peekable();
+    while let Some((position, character)) = chars.next() {
+        match character {
+            '0'..='9' | '.' => {
+                let mut end = position + character.len_utf8();
+                while let Some(&(next, c)) = chars.peek() {
+                    if c.is_ascii_digit() || c == '.' {
+                        end = next + c.len_utf8();
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+                if count == 3 {
+                    return Err(DmsError::InvalidNumber { position });
+                }
+                parts[count] = input[position..end]
+                    .parse()
+                    .map_err(|_| DmsError::InvalidNumber { position })?;
+                count += 1;
+            }
+            '-' | '\u{2212}' if count == 0 && !negative => negative = true,
+            '+' if count == 0 => {}
+            'N' | 'n' | 'E' | 'e' | 'S' | 's' | 'W' | 'w' => {
+                if hemisphere.is_some() {
+                    return Err(DmsError::ConflictingSign);
+                }
+                hemisphere = Some(matches!(character, 'S' | 's' | 'W' | 'w'));
+            }
+            '°' | 'º' | 'd' | 'D' | '\'' | '′' | '’' | '"' | '″' | '”' | ':' | ',' => {}
+            c if c.is_whitespace() => {}
+            c => {
+                return Err(DmsError::UnexpectedCharacter {
+                    character: c,
+                    position,
+                })
+            }
+        }
+    }
+
+    if count == 0 {
+        return Err(DmsError::Empty);
+    }
+    if (count > 1 && parts[1] >= 60.) || (count > 2 && parts[2] >= 60.) {
+        return Err(DmsError::OutOfRange);
+    }
+    if negative && hemisphere.is_some() {
+        return Err(DmsError::ConflictingSign);
+    }
+
+    let value = parts[0] + parts[1] / 60. + parts[2] / 3600.;
+    if negative || hemisphere == Some(true) {
+        Ok(-value)
+    } else {
+        Ok(value)
+    }
+}
+
+/// A coordinate split into degrees, minutes and seconds for display,
+/// e.g. `40°47'18.761"N`. The formatter precision sets the decimals of the seconds (default 3).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Dms {
+    pub degrees: u32,
+    pub minutes: u32,
+    pub seconds: f64,
+    pub hemisphere: char,
+}
+
+impl Dms {
+    pub fn from_decimal(value: f64, axis: Axis) -> Self {
+        let hemisphere = match (axis, value < 0.) {
+            (Axis::Latitude, false) => 'N',
+            (Axis::Latitude, true) => 'S',
+            (Axis::Longitude, false) => 'E',
+            (Axis::Longitude, true) => 'W',
+        };
+        let value = fabs(value);
+        let degrees = trunc(value);
+        let minutes = trunc((value - degrees) * 60.);
+        let seconds = (value - degrees - minutes / 60.) * 3600.;
+
+        Self {
+            degrees: degrees as u32,
+            minutes: minutes as u32,
+            seconds,
+            hemisphere,
+        }
+    }
+
+    pub fn to_decimal(&self) -> f64 {
+        let value = self.degrees as f64 + self.minutes as f64 / 60. + self.seconds / 3600.;
+        if matches!(self.hemisphere, 'S' | 'W') {
+            -value
+        } else {
+            value
+        }
+    }
+}
+
+impl fmt::Display for Dms {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let precision = f.precision().unwrap_or(3);
+        let (mut degrees, mut minutes) = (self.degrees, self.minutes);
+
+        // Carry rounding up, so 59.9996" at 3 decimals becomes the next minute instead of 60"
+        let scale = libm::pow(10., precision as f64);
+        let mut seconds = round(self.seconds * scale) / scale;
+        if seconds >= 60. {
+            seconds -= 60.;
+            minutes += 1;
+        }
+        if minutes >= 60 {
+            minutes -= 60;
+            degrees += 1;
+        }
+
+        write!(
+            f,
+            "{}°{}'{:.*}\"{}",
+            degrees, minutes, precision, seconds, self.hemisphere
+        )
+    }
+}
@@ -0,0 +1,138 @@
+use core::f64::consts::FRAC_PI_2;
+use libm::{cos, sin};
+
+use crate::circle::Circle;
+use crate::location::Location;
+use crate::sphere::{arc_length, Vector3};
+
+/// Leeway when checking containment, so points on the boundary aren't treated as outside.
+const EPSILON: f64 = 1e-12;
+
+/// A cap on the unit sphere, with the radius as an angle.
+#[derive(Debug, Clone, Copy)]
+struct Cap {
+    centre: Vector3,
+    radius: f64,
+}
+
+impl Cap {
+    fn contains(&self, point: &Vector3) -> bool {
+        self.centre.angle(point) <= self.radius + EPSILON
+    }
+
+    fn from_two(a: &Vector3, b: &Vector3) -> Self {
+        let centre = a.add(b).normalize().unwrap_or(*a);
+        Cap {
+            centre,
+            radius: centre.angle(a).max(centre.angle(b)),
+        }
+    }
+
+    /// The smallest cap with all three points on its boundary.
+    fn from_three(a: &Vector3, b: &Vector3, c: &Vector3) -> Self {
+        let normal = b.sub(a).cross(&c.sub(a)).normalize();
+        match normal {
+            Some(normal) => {
+                let centre = if normal.dot(a) < 0. {
+                    normal.scale(-1.)
+                } else {
+                    normal
+                };
+                let radius = centre.angle(a).max(centre.angle(b)).max(centre.angle(c));
+                Cap { centre, radius }
+            }
+            None => Cap::from_two(a, b),
+        }
+    }
+
+    fn to_circle(self) -> Circle {
+        Circle::from(self.centre.to_location(), arc_length(self.radius))
+    }
+}
+
+impl Circle {
+    /// The smallest circle containing every location, found with Welzl's algorithm on the sphere.
+    ///
+    /// Unlike `find_center_point`, the centre minimises the distance to the farthest location.
+    /// Returns `None` when there are no locations, or when they don't fit within a hemisphere,
+    /// where the algorithm doesn't apply and no circle smaller than a hemisphere exists.
+    pub fn minimum_enclosing(locations: &[Location]) -> Option<Circle> {
+        let mut points: Vec<Vector3> = locations.iter().map(Vector3::from_location).collect();
+
+        // The expected running time is linear when points are visited in random order
+        let mut state = 0x9e37_79b9_7f4a_7c15u64;
+        for i in (1..points.len()).rev() {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            points.swap(i, (state % (i as u64 + 1)) as usize);
+        }
+
+        let first = points.first()?;
+        let mut cap = Cap {
+            centre: *first,
+            radius: 0.,
+        };
+        for i in 1..points.len() {
+            if cap.contains(&points[i]) {
+                continue;
+            }
+            cap = Cap {
+                centre: points[i],
+                radius: 0.,
+            };
+            for j in 0..i {
+                if cap.contains(&points[j]) {
+                    continue;
+                }
+                cap = Cap::from_two(&points[i], &points[j]);
+                for k in 0..j {
+                    if !cap.contains(&points[k]) {
+                        cap = Cap::from_three(&points[i], &points[j], &points[k]);
+                    }
+                }
+            }
+        }
+
+        // Within a hemisphere the result is a cap under 90° holding every point. Otherwise
+        // no such cap exists, so this catches every input that breaks the precondition.
+        if cap.radius >= FRAC_PI_2 || !points.iter().all(|point| cap.contains(point)) {
+            return None;
+        }
+        Some(cap.to_circle())
+    }
+
+    /// A quick circle containing every location, using Ritter's algorithm in a single pass
+    /// after finding two far apart points. It's typically within a few percent of the minimum.
+    /// Returns `None` when there are no locations.
+    pub fn bounding(locations: &[Location]) -> Option<Circle> {
+        let points: Vec<Vector3> = locations.iter().map(Vector3::from_location).collect();
+        let farthest = |from: &Vector3| {
+            points
+                .iter()
+                .max_by(|a, b| from.angle(a).total_cmp(&from.angle(b)))
+                .copied()
+        };
+        let a = farthest(points.first()?)?;
+        let b = farthest(&a)?;
+        let mut cap = Cap::from_two(&a, &b);
+
+        for point in &points {
+            let distance = cap.centre.angle(point);
+            if distance <= cap.radius + EPSILON {
+                continue;
+            }
+            // Grow just enough to reach the point, moving the centre toward it
+            let radius = (cap.radius + distance) / 2.;
+            let shift = distance - radius;
+            let toward = point
+                .sub(&cap.centre.scale(cap.centre.dot(point)))
+                .normalize();
+            if let Some(toward) = toward {
+                cap.centre = cap.centre.scale(cos(shift)).add(&toward.scale(sin(shift)));
+            }
+            cap.radius = radius.max(cap.centre.angle(point));
+        }
+        Some(cap.to_circle())
+    }
+}
@@ -0,0 +1,145 @@
+use core::fmt;
+
+#[cfg(feature = "std")]
+use crate::csv::CsvError;
+use crate::dms::DmsError;
+#[cfg(all(feature = "serde", feature = "std"))]
+use crate::geojson::GeoJsonError;
+#[cfg(feature = "std")]
+use crate::gpx::GpxError;
+#[cfg(feature = "std")]
+use crate::hull::HullError;
+#[cfg(feature = "std")]
+use crate::kml::KmlError;
+use crate::location::LocationError;
+use crate::measurement::{DistanceError, ParseDistanceError};
+use crate::nmea::NmeaError;
+#[cfg(feature = "std")]
+use crate::polyline::PolylineError;
+use crate::speed::ParseSpeedError;
+use crate::time::TimestampError;
+#[cfg(feature = "std")]
+use crate::wkb::WkbError;
+#[cfg(feature = "std")]
+use crate::wkt::WktError;
+#[cfg(feature = "std")]
+use crate::xml::XmlError;
+
+/// Any error produced by this crate, so callers can use `?` across modules with a single error type.
+///
+/// Each variant wraps the error from one module. The wrapper is transparent: it displays the
+/// wrapped error's message and passes on its `source`, so error reporters don't print it twice.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    Location(LocationError),
+    Distance(DistanceError),
+    ParseDistance(ParseDistanceError),
+    ParseSpeed(ParseSpeedError),
+    Dms(DmsError),
+    Nmea(NmeaError),
+    Timestamp(TimestampError),
+    #[cfg(feature = "std")]
+    Csv(CsvError),
+    #[cfg(all(feature = "serde", feature = "std"))]
+    GeoJson(GeoJsonError),
+    #[cfg(feature = "std")]
+    Gpx(GpxError),
+    #[cfg(feature = "std")]
+    Hull(HullError),
+    #[cfg(feature = "std")]
+    Kml(KmlError),
+    #[cfg(feature = "std")]
+    Polyline(PolylineError),
+    #[cfg(feature = "std")]
+    Wkb(WkbError),
+    #[cfg(feature = "std")]
+    Wkt(WktError),
+    #[cfg(feature = "std")]
+    Xml(XmlError),
+}
+
+impl Error {
+    fn inner(&self) -> &(dyn core::error::Error + 'static) {
+        match self {
+            Error::Location(error) => error,
+            Error::Distance(error) => error,
+            Error::ParseDistance(error) => error,
+            Error::ParseSpeed(error) => error,
+            Error::Dms(error) => error,
+            Error::Nmea(error) => error,
+            Error::Timestamp(error) => error,
+            #[cfg(feature = "std")]
+            Error::Csv(error) => error,
+            #[cfg(all(feature = "serde", feature = "std"))]
+            Error::GeoJson(error) => error,
+            #[cfg(feature = "std")]
+            Error::Gpx(error) => error,
+            #[cfg(feature = "std")]
+            Error::Hull(error) => error,
+            #[cfg(feature = "std")]
+            Error::Kml(error) => error,
+            #[cfg(feature = "std")]
+            Error::Polyline(error) => error,
+            #[cfg(feature = "std")]
+            Error::Wkb(error) => error,
+            #[cfg(feature = "std")]
+            Error::Wkt(error) => error,
+            #[cfg(feature = "std")]
+            Error::Xml(error) => error,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(self.inner(), f)
+    }
+}
+
+impl core::error::Error for Error {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        self.inner().source()
+    }
+}
+
+macro_rules! from_error {
+    ($($(#[$cfg:meta])* $variant:ident($error:ty),)*) => {
+        $(
+            $(#[$cfg])*
+            impl From<$error> for Error {
+                fn from(error: $error) -> Self {
+                    Error::$variant(error)
+                }
+            }
+        )*
+    };
+}
+
+from_error! {
+    Location(LocationError),
+    Distance(DistanceError),
+    ParseDistance(ParseDistanceError),
+    ParseSpeed(ParseSpeedError),
+    Dms(DmsError),
+    Nmea(NmeaError),
+    Timestamp(TimestampError),
+    #[cfg(feature = "std")]
+    Csv(CsvError),
+    #[cfg(all(feature = "serde", feature = "std"))]
+    GeoJson(GeoJsonError),
+    #[cfg(feature = "std")]
+    Gpx(GpxError),
+    #[cfg(feature = "std")]
+    Hull(HullError),
+    #[cfg(feature = "std")]
+    Kml(KmlError),
+    #[cfg(feature = "std")]
+    Polyline(PolylineError),
+    #[cfg(feature = "std")]
+    Wkb(WkbError),
+    #[cfg(feature = "std")]
+    Wkt(WktError),
+    #[cfg(feature = "std")]
+    Xml(XmlError),
+}
@@ -0,0 +1,181 @@
+use core::fmt::{self, Write};
+use libm::{fabs, pow, round};
+
+use crate::measurement::{Distance, DistanceUnit};
+
+/// A family of units to pick from when formatting a distance for people.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum UnitSystem {
+    /// Centimeters below a meter, meters below a kilometer, then kilometers.
+    #[default]
+    Metric,
+    /// Inches below a foot, feet below a tenth of a mile, then miles.
+    Imperial,
+    /// Fathoms below a cable, cables below a nautical mile, then nautical miles.
+    Nautical,
+}
+
+impl UnitSystem {
+    /// Units from smallest to largest, each with the distance in meters where it takes over.
+    fn ladder(&self) -> [(DistanceUnit, f64); 3] {
+        match self {
+            UnitSystem::Metric => [
+                (DistanceUnit::Centimeters, 0.),
+                (DistanceUnit::Meters, 1.),
+                (DistanceUnit::Kilometers, 1000.),
+            ],
+            UnitSystem::Imperial => [
+                (DistanceUnit::Inches, 0.),
+                (DistanceUnit::Feet, DistanceUnit::Feet.in_meters()),
+                (DistanceUnit::Miles, DistanceUnit::Miles.in_meters() / 10.),
+            ],
+            UnitSystem::Nautical => [
+                (DistanceUnit::Fathoms, 0.),
+                (DistanceUnit::Cables, DistanceUnit::Cables.in_meters()),
+                (
+                    DistanceUnit::NauticalMiles,
+                    DistanceUnit::NauticalMiles.in_meters(),
+                ),
+            ],
+        }
+    }
+}
+
+/// Options for formatting a distance in the most readable unit, like `850 m` or `1.2 km`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DistanceFormat {
+    pub system: UnitSystem,
+    /// The most decimal places to show. Trailing zeros are dropped, so `1.0 km` prints as `1 km`.
+    pub precision: usize,
+    /// Whether to write `kilometers` rather than `km`. Full names are pluralised unless the value is exactly one.
+    pub full_names: bool,
+}
+
+impl Default for DistanceFormat {
+    fn default() -> Self {
+        Self {
+            system: UnitSystem::Metric,
+            precision: 1,
+            full_names: false,
+        }
+    }
+}
+
+impl DistanceFormat {
+    pub fn new(system: UnitSystem) -> Self {
+        Self {
+            system,
+            ..Self::default()
+        }
+    }
+
+    /// The unit `distance` will be shown in, and its value rounded to the precision.
+    pub fn select(&self, distance: &Distance) -> (DistanceUnit, f64) {
+        let meters = distance.meters();
+        let ladder = self.system.ladder();
+        let magnitude = fabs(meters);
+        let mut rung = if magnitude == 0. {
+            1
+        } else {
+            ladder
+                .iter()
+                .rposition(|&(_, threshold)| magnitude >= threshold)
+                .unwrap_or(0)
+        };
+
+        let factor = pow(10., self.precision as f64);
+        let rounded = |unit: DistanceUnit| round(meters / unit.in_meters() * factor) / factor;
+        // Rounding can carry a value up to the next unit, as with 999.96 m becoming 1 km
+        while rung + 1 < ladder.len() {
+            let (unit, _) = ladder[rung];
+            if fabs(rounded(unit)) * unit.in_meters() < ladder[rung + 1].1 {
+                break;
+            }
+            rung += 1;
+        }
+        let unit = ladder[rung].0;
+        let value = rounded(unit);
+        // Avoid printing "-0"
+        (unit, if value == 0. { 0. } else { value })
+    }
+
+    /// Writes `distance` to any `core::fmt::Write`, so it works without an allocator.
+    pub fn write<W: Write>(&self, distance: &Distance, out: &mut W) -> fmt::Result {
+        let (unit, value) = self.select(distance);
+        let mut number = Buffer::default();
+        if write!(number, "{:.*}", self.precision, value).is_ok() {
+            out.write_str(number.trimmed())?;
+        } else {
+            write!(out, "{:.*}", self.precision, value)?;
+        }
+
+        let name = match (self.full_names, value == 1. || value == -1.) {
+            (false, _) => unit.abbreviation(),
+            (true, true) => unit.singular_name(),
+            (true, false) => unit.name(),
+        };
+        write!(out, " {}", name)
+    }
+}
+
+/// A distance paired with a `DistanceFormat`, for use with `format!` and `to_string`.
+#[derive(Debug, Clone, Copy)]
+pub struct FormattedDistance<'a> {
+    distance: &'a Distance,
+    format: DistanceFormat,
+}
+
+impl fmt::Display for FormattedDistance<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.format.write(self.distance, f)
+    }
+}
+
+impl Distance {
+    /// Displays the distance in the most readable unit of a system, like `850 m`, `1.2 km` or `0.4 mi`.
+    pub fn humanize(&self, format: DistanceFormat) -> FormattedDistance<'_> {
+        FormattedDistance {
+            distance: self,
+            format,
+        }
+    }
+}
+
+/// A fixed size buffer for a formatted number, so trailing zeros can be trimmed without allocating.
+struct Buffer {
+    bytes: [u8; 64],
+    length: usize,
+}
+
+impl Default for Buffer {
+    fn default() -> Self {
+        Self {
+            bytes: [0; 64],
+            length: 0,
+        }
+    }
+}
+
+impl Buffer {
+    fn trimmed(&self) -> &str {
+        // Only ASCII is ever written
+        let text = core::str::from_utf8(&self.bytes[..self.length]).unwrap_or_default();
+        if text.contains('.') {
+            text.trim_end_matches('0').trim_end_matches('.')
+        } else {
+            text
+        }
+    }
+}
+
+impl Write for Buffer {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let end = self.length + s.len();
+        self.bytes
+            .get_mut(self.length..end)
+            .ok_or(fmt::Error)?
+            .copy_from_slice(s.as_bytes());
+        self.length = end;
+        Ok(())
+    }
+}
@@ -0,0 +1,161 @@
+use core::time::Duration;
+
+use crate::circle::Circle;
+use crate::location::Location;
+use crate::measurement::Distance;
+use crate::sphere::{angle_to_arc, arc_length, ring_contains, Vector3};
+use crate::time::Timestamp;
+
+/// An area to watch for devices entering and leaving.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Geofence<'a> {
+    Circle(Circle),
+    /// A ring of vertices with edges along great circles. It may be open or closed, and
+    /// borrows the caller's storage so fences work without an allocator.
+    Polygon(&'a [Location]),
+}
+
+impl Geofence<'_> {
+    /// The distance from the fence's boundary in meters, negative inside.
+    fn signed_distance(&self, location: &Location) -> f64 {
+        let point = Vector3::from_location(location);
+        match self {
+            Geofence::Circle(circle) => {
+                let angle = Vector3::from_location(&circle.centre).angle(&point);
+                arc_length(angle).meters() - circle.radius.meters()
+            }
+            Geofence::Polygon(ring) => {
+                if ring.is_empty() {
+                    return f64::INFINITY;
+                }
+                let angle = (0..ring.len())
+                    .map(|i| {
+                        let a = Vector3::from_location(&ring[i]);
+                        let b = Vector3::from_location(&ring[(i + 1) % ring.len()]);
+                        angle_to_arc(&point, &a, &b)
+                    })
+                    .fold(f64::INFINITY, f64::min);
+                let distance = arc_length(angle).meters();
+                if ring_contains(ring, &point) {
+                    -distance
+                } else {
+                    distance
+                }
+            }
+        }
+    }
+
+    pub fn contains(&self, location: &Location) -> bool {
+        self.signed_distance(location) <= 0.
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GeofenceEventKind {
+    Enter,
+    Exit,
+    /// The device has been inside for the monitor's dwell time. Sent once per visit.
+    Dwell,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GeofenceEvent {
+    /// The index of the fence in the monitor's fences.
+    pub fence: usize,
+    pub kind: GeofenceEventKind,
+    /// The time of the update that caused the event.
+    pub time: Timestamp,
+}
+
+/// What a monitor knows about one device and one fence. Keep one per fence for each device.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct FenceState {
+    entered: Option<Timestamp>,
+    dwelled: bool,
+}
+
+impl FenceState {
+    /// A device that hasn't been seen inside the fence yet. Usable in `static`s and array initialisers.
+    pub const fn new() -> Self {
+        Self {
+            entered: None,
+            dwelled: false,
+        }
+    }
+
+    pub fn is_inside(&self) -> bool {
+        self.entered.is_some()
+    }
+
+    /// When the device entered the fence, if it's inside.
+    pub fn entered(&self) -> Option<Timestamp> {
+        self.entered
+    }
+}
+
+/// Turns position updates into `Enter`, `Exit` and `Dwell` events for a set of fences.
+///
+/// The monitor holds no per-device state, so it needs no allocator: the caller keeps a
+/// `[FenceState]` with one entry per fence for each device, and passes it to `update`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GeofenceMonitor<'a> {
+    pub fences: &'a [Geofence<'a>],
+    /// How far outside a fence's boundary a device must be before it exits, so GPS noise
+    /// around the edge doesn't cause repeated enter and exit events.
+    pub hysteresis: Distance,
+    /// How long a device must stay inside before a `Dwell` event.
+    pub dwell: Duration,
+}
+
+impl<'a> GeofenceMonitor<'a> {
+    pub fn new(fences: &'a [Geofence<'a>], hysteresis: Distance, dwell: Duration) -> Self {
+        Self {
+            fences,
+            hysteresis,
+            dwell,
+        }
+    }
+
+    /// Processes one position update for a device, calling `on_event` for each event in fence order.
+    ///
+    /// `states` is that device's state, with one entry per fence. Updates should arrive in time order.
+    /// Panics if `states` doesn't have the same length as `fences`.
+    pub fn update(
+        &self,
+        states: &mut [FenceState],
+        location: &Location,
+        time: Timestamp,
+        mut on_event: impl FnMut(GeofenceEvent),
+    ) {
+        assert_eq!(states.len(), self.fences.len(), "one state per fence");
+        let margin = self.hysteresis.meters().max(0.);
+        let dwell = self.dwell.as_secs_f64();
+
+        for (fence, (geofence, state)) in self.fences.iter().zip(states.iter_mut()).enumerate() {
+            let distance = geofence.signed_distance(location);
+            let mut emit = |kind| on_event(GeofenceEvent { fence, kind, time });
+
+            match state.entered {
+                None if distance <= 0. => {
+                    *state = FenceState {
+                        entered: Some(time),
+                        dwelled: false,
+                    };
+                    emit(GeofenceEventKind::Enter);
+                }
+                Some(_) if distance > margin => {
+                    *state = FenceState::new();
+                    emit(GeofenceEventKind::Exit);
+                }
+                _ => {}
+            }
+
+            if let Some(entered) = state.entered {
+                if !state.dwelled && time.seconds_since(&entered) >= dwell {
+                    state.dwelled = true;
+                    emit(GeofenceEventKind::Dwell);
+                }
+            }
+        }
+    }
+}
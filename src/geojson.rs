@@ -0,0 +1,304 @@
+use core::fmt;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::{json, Map, Value};
+
+use crate::geometry::{Geometry, LineString, Polygon};
+use crate::location::Location;
+
+/// An error produced while reading GeoJSON.
+#[derive(Debug, Clone, PartialEq)]
+pub enum GeoJsonError {
+    /// The input was not valid JSON.
+    Json(String),
+    /// A required member such as `type` or `coordinates` was missing.
+    MissingMember(&'static str),
+    /// The `type` member didn't match what was expected.
+    UnexpectedType {
+        expected: &'static str,
+        found: String,
+    },
+    /// The coordinates of a geometry had the wrong shape.
+    InvalidCoordinates { geometry: &'static str },
+}
+
+impl fmt::Display for GeoJsonError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GeoJsonError::Json(message) => write!(f, "invalid JSON: {}", message),
+            GeoJsonError::MissingMember(member) => write!(f, "missing member {:?}", member),
+            GeoJsonError::UnexpectedType { expected, found } => {
+                write!(f, "expected {}, found type {:?}", expected, found)
+            }
+            GeoJsonError::InvalidCoordinates { geometry } => {
+                write!(f, "invalid coordinates for {}", geometry)
+            }
+        }
+    }
+}
+
+impl std::error::Error for GeoJsonError {}
+
+impl From<serde_json::Error> for GeoJsonError {
+    fn from(error: serde_json::Error) -> Self {
+        GeoJsonError::Json(error.to_string())
+    }
+}
+
+/// A GeoJSON feature: an optional geometry with arbitrary properties.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Feature {
+    pub id: Option<Value>,
+    pub geometry: Option<Geometry>,
+    pub properties: Map<String, Value>,
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct FeatureCollection {
+    pub features: Vec<Feature>,
+}
+
+fn position(location: &Location) -> Value {
+    json!([location.longitude, location.latitude])
+}
+
+fn positions(locations: &[Location]) -> Value {
+    Value::Array(locations.iter().map(position).collect())
+}
+
+fn rings(polygon: &Polygon) -> Value {
+    Value::Array(
+        polygon
+            .rings()
+            .map(|ring| positions(&ring.closed_points()))
+            .collect(),
+    )
+}
+
+fn type_member(value: &Value) -> Result<&str, GeoJsonError> {
+    value
+        .get("type")
+        .ok_or(GeoJsonError::MissingMember("type"))?
+        .as_str()
+        .ok_or(GeoJsonError::MissingMember("type"))
+}
+
+fn expect_type(value: &Value, expected: &'static str) -> Result<(), GeoJsonError> {
+    let found = type_member(value)?;
+    if found == expected {
+        Ok(())
+    } else {
+        Err(GeoJsonError::UnexpectedType {
+            expected,
+            found: found.into(),
+        })
+    }
+}
+
+fn parse_position(value: &Value) -> Option<Location> {
+    match value.as_array()?.as_slice() {
+        [lng, lat, ..] => Some(Location::from(lat.as_f64()?, lng.as_f64()?)),
+        _ => None,
+    }
+}
+
+fn parse_positions(value: &Value) -> Option<Vec<Location>> {
+    value.as_array()?.iter().map(parse_position).collect()
+}
+
+fn parse_polygon(value: &Value) -> Option<Polygon> {
+    let mut rings = value
+        .as_array()?
+        .iter()
+        .map(|ring| parse_positions(ring).map(LineString::from));
+    let exterior = rings.next()??;
+    let interiors = rings.collect::<Option<Vec<_>>>()?;
+    Some(Polygon::from(exterior, interiors))
+}
+
+fn parse_each<T>(value: &Value, parse: fn(&Value) -> Option<T>) -> Option<Vec<T>> {
+    value.as_array()?.iter().map(parse).collect()
+}
+
+impl Geometry {
+    /// Converts the geometry into a GeoJSON geometry object with `[lng, lat]` positions.
+    pub fn to_geojson(&self) -> Value {
+        let coordinates = match self {
+            Geometry::Point(location) => position(location),
+            Geometry::MultiPoint(locations) => positions(locations),
+            Geometry::LineString(line) => positions(&line.points),
+            Geometry::MultiLineString(lines) => {
+                lines.iter().map(|line| positions(&line.points)).collect()
+            }
+            Geometry::Polygon(polygon) => rings(polygon),
+            Geometry::MultiPolygon(polygons) => polygons.iter().map(rings).collect(),
+        };
+
+        json!({
+            "type": self.type_name(),
+            "coordinates": coordinates,
+        })
+    }
+
+    /// Reads a GeoJSON geometry object.
+    pub fn from_geojson(value: &Value) -> Result<Self, GeoJsonError> {
+        let kind = type_member(value)?;
+        let coordinates = value
+            .get("coordinates")
+            .ok_or(GeoJsonError::MissingMember("coordinates"))?;
+
+        let (geometry, name) = match kind {
+            "Point" => (parse_position(coordinates).map(Geometry::Point), "Point"),
+            "MultiPoint" => (
+                parse_positions(coordinates).map(Geometry::MultiPoint),
+                "MultiPoint",
+            ),
+            "LineString" => (
+                parse_positions(coordinates).map(|points| LineString::from(points).into()),
+                "LineString",
+            ),
+            "MultiLineString" => (
+                parse_each(coordinates, |line| {
+                    parse_positions(line).map(LineString::from)
+                })
+                .map(Geometry::MultiLineString),
+                "MultiLineString",
+            ),
+            "Polygon" => (parse_polygon(coordinates).map(Geometry::Polygon), "Polygon"),
+            "MultiPolygon" => (
+                parse_each(coordinates, parse_polygon).map(Geometry::MultiPolygon),
+                "MultiPolygon",
+            ),
+            other => {
+                return Err(GeoJsonError::UnexpectedType {
+                    expected: "geometry",
+                    found: other.into(),
+                })
+            }
+        };
+
+        geometry.ok_or(GeoJsonError::InvalidCoordinates { geometry: name })
+    }
+
+    pub fn to_geojson_string(&self) -> String {
+        self.to_geojson().to_string()
+    }
+
+    pub fn from_geojson_str(input: &str) -> Result<Self, GeoJsonError> {
+        Self::from_geojson(&serde_json::from_str(input)?)
+    }
+}
+
+impl Feature {
+    pub fn from(geometry: Geometry) -> Self {
+        Self {
+            id: None,
+            geometry: Some(geometry),
+            properties: Map::new(),
+        }
+    }
+
+    /// Sets a property, returning the feature for chaining.
+    pub fn with_property(mut self, key: &str, value: impl Into<Value>) -> Self {
+        self.properties.insert(key.into(), value.into());
+        self
+    }
+
+    pub fn to_geojson(&self) -> Value {
+        let mut object = Map::new();
+        object.insert("type".into(), "Feature".into());
+        if let Some(id) = &self.id {
+            object.insert("id".into(), id.clone());
+        }
+        object.insert(
+            "geometry".into(),
+            self.geometry
+                .as_ref()
+                .map_or(Value::Null, Geometry::to_geojson),
+        );
+        object.insert("properties".into(), Value::Object(self.properties.clone()));
+        Value::Object(object)
+    }
+
+    pub fn from_geojson(value: &Value) -> Result<Self, GeoJsonError> {
+        expect_type(value, "Feature")?;
+
+        let geometry = match value.get("geometry") {
+            None | Some(Value::Null) => None,
+            Some(geometry) => Some(Geometry::from_geojson(geometry)?),
+        };
+        let properties = match value.get("properties") {
+            Some(Value::Object(properties)) => properties.clone(),
+            _ => Map::new(),
+        };
+
+        Ok(Self {
+            id: value.get("id").cloned(),
+            geometry,
+            properties,
+        })
+    }
+
+    pub fn to_geojson_string(&self) -> String {
+        self.to_geojson().to_string()
+    }
+
+    pub fn from_geojson_str(input: &str) -> Result<Self, GeoJsonError> {
+        Self::from_geojson(&serde_json::from_str(input)?)
+    }
+}
+
+impl FeatureCollection {
+    pub fn from(features: Vec<Feature>) -> Self {
+        Self { features }
+    }
+
+    pub fn to_geojson(&self) -> Value {
+        json!({
+            "type": "FeatureCollection",
+            "features": self.features.iter().map(Feature::to_geojson).collect::<Vec<_>>(),
+        })
+    }
+
+    pub fn from_geojson(value: &Value) -> Result<Self, GeoJsonError> {
+        expect_type(value, "FeatureCollection")?;
+
+        let features = value
+            .get("features")
+            .and_then(Value::as_array)
+            .ok_or(GeoJsonError::MissingMember("features"))?
+            .iter()
+            .map(Feature::from_geojson)
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self { features })
+    }
+
+    pub fn to_geojson_string(&self) -> String {
+        self.to_geojson().to_string()
+    }
+
+    pub fn from_geojson_str(input: &str) -> Result<Self, GeoJsonError> {
+        Self::from_geojson(&serde_json::from_str(input)?)
+    }
+}
+
+macro_rules! impl_geojson_serde {
+    ($($ty:ty),*) => {
+        $(
+            impl Serialize for $ty {
+                fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                    self.to_geojson().serialize(serializer)
+                }
+            }
+
+            impl<'de> Deserialize<'de> for $ty {
+                fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                    let value = Value::deserialize(deserializer)?;
+                    Self::from_geojson(&value).map_err(de::Error::custom)
+                }
+            }
+        )*
+    };
+}
+
+impl_geojson_serde!(Geometry, Feature, FeatureCollection);
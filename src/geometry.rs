@@ -0,0 +1,95 @@
+use crate::location::Location;
+
+/// A sequence of connected locations, such as a route or a polygon ring.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct LineString {
+    pub points: Vec<Location>,
+}
+
+impl LineString {
+    pub fn from(points: Vec<Location>) -> Self {
+        Self { points }
+    }
+
+    /// Returns true if the first and last points are the same.
+    pub fn is_closed(&self) -> bool {
+        match (self.points.first(), self.points.last()) {
+            (Some(first), Some(last)) => first == last,
+            _ => false,
+        }
+    }
+
+    /// Returns the points with the first point repeated at the end if it isn't already.
+    pub fn closed_points(&self) -> Vec<Location> {
+        let mut points = self.points.clone();
+        if !self.is_closed() {
+            if let Some(first) = self.points.first() {
+                points.push(first.clone());
+            }
+        }
+        points
+    }
+}
+
+/// A polygon made of an exterior ring and any number of holes.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Polygon {
+    pub exterior: LineString,
+    pub interiors: Vec<LineString>,
+}
+
+impl Polygon {
+    pub fn from(exterior: LineString, interiors: Vec<LineString>) -> Self {
+        Self {
+            exterior,
+            interiors,
+        }
+    }
+
+    /// Iterates over the exterior ring followed by the interior rings.
+    pub fn rings(&self) -> impl Iterator<Item = &LineString> {
+        core::iter::once(&self.exterior).chain(self.interiors.iter())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Geometry {
+    Point(Location),
+    MultiPoint(Vec<Location>),
+    LineString(LineString),
+    MultiLineString(Vec<LineString>),
+    Polygon(Polygon),
+    MultiPolygon(Vec<Polygon>),
+}
+
+impl Geometry {
+    /// The name of the geometry type as used by GeoJSON and WKT.
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Geometry::Point(_) => "Point",
+            Geometry::MultiPoint(_) => "MultiPoint",
+            Geometry::LineString(_) => "LineString",
+            Geometry::MultiLineString(_) => "MultiLineString",
+            Geometry::Polygon(_) => "Polygon",
+            Geometry::MultiPolygon(_) => "MultiPolygon",
+        }
+    }
+}
+
+impl From<Location> for Geometry {
+    fn from(location: Location) -> Self {
+        Geometry::Point(location)
+    }
+}
+
+impl From<LineString> for Geometry {
+    fn from(line: LineString) -> Self {
+        Geometry::LineString(line)
+    }
+}
+
+impl From<Polygon> for Geometry {
+    fn from(polygon: Polygon) -> Self {
+        Geometry::Polygon(polygon)
+    }
+}
@@ -0,0 +1,323 @@
+use core::fmt;
+use core::fmt::Write;
+use core::str::FromStr;
+
+use crate::location::Location;
+use crate::measurement::Distance;
+use crate::time::Timestamp;
+use crate::xml::{self, Element, XmlError};
+
+/// An error produced while reading a GPX document.
+#[derive(Debug, Clone, PartialEq)]
+pub enum GpxError {
+    Xml(XmlError),
+    /// The root element wasn't `<gpx>`.
+    NotGpx {
+        root: String,
+    },
+    /// A point was missing its `lat` or `lon` attribute.
+    MissingAttribute {
+        element: String,
+        attribute: &'static str,
+    },
+    /// A coordinate or elevation couldn't be parsed as a number.
+    InvalidNumber {
+        element: String,
+        value: String,
+    },
+    /// A `<time>` element wasn't a valid RFC 3339 timestamp.
+    InvalidTime {
+        value: String,
+    },
+}
+
+impl fmt::Display for GpxError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GpxError::Xml(error) => write!(f, "invalid GPX: {}", error),
+            GpxError::NotGpx { root } => write!(f, "expected <gpx> root, found <{}>", root),
+            GpxError::MissingAttribute { element, attribute } => {
+                write!(f, "<{}> is missing the {:?} attribute", element, attribute)
+            }
+            GpxError::InvalidNumber { element, value } => {
+                write!(f, "invalid number {:?} in <{}>", value, element)
+            }
+            GpxError::InvalidTime { value } => write!(f, "invalid time {:?}", value),
+        }
+    }
+}
+
+impl std::error::Error for GpxError {}
+
+impl From<XmlError> for GpxError {
+    fn from(error: XmlError) -> Self {
+        GpxError::Xml(error)
+    }
+}
+
+/// A GPX point, used for waypoints, route points and track points.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct GpxWaypoint {
+    pub location: Location,
+    /// Elevation in meters.
+    pub elevation: Option<f64>,
+    pub time: Option<Timestamp>,
+    pub name: Option<String>,
+    pub description: Option<String>,
+}
+
+impl GpxWaypoint {
+    pub fn from(location: Location) -> Self {
+        Self {
+            location,
+            ..Self::default()
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct GpxRoute {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub points: Vec<GpxWaypoint>,
+}
+
+/// A continuous span of track points, e.g. between GPS signal losses.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct GpxTrackSegment {
+    pub points: Vec<GpxWaypoint>,
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct GpxTrack {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub segments: Vec<GpxTrackSegment>,
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Gpx {
+    pub creator: Option<String>,
+    pub waypoints: Vec<GpxWaypoint>,
+    pub routes: Vec<GpxRoute>,
+    pub tracks: Vec<GpxTrack>,
+}
+
+fn path_distance(points: &[GpxWaypoint]) -> Distance {
+    points
+        .windows(2)
+        .fold(Distance::from_meters(0.), |total, pair| {
+            total + pair[0].location.distance(&pair[1].location)
+        })
+}
+
+impl GpxRoute {
+    pub fn locations(&self) -> Vec<Location> {
+        self.points
+            .iter()
+            .map(|point| point.location.clone())
+            .collect()
+    }
+
+    pub fn distance(&self) -> Distance {
+        path_distance(&self.points)
+    }
+}
+
+impl GpxTrackSegment {
+    pub fn locations(&self) -> Vec<Location> {
+        self.points
+            .iter()
+            .map(|point| point.location.clone())
+            .collect()
+    }
+
+    pub fn distance(&self) -> Distance {
+        path_distance(&self.points)
+    }
+}
+
+impl GpxTrack {
+    /// The total length of all segments. Gaps between segments are not counted.
+    pub fn distance(&self) -> Distance {
+        self.segments
+            .iter()
+            .fold(Distance::from_meters(0.), |total, segment| {
+                total + segment.distance()
+            })
+    }
+}
+
+fn parse_number(element: &Element, text: &str) -> Result<f64, GpxError> {
+    text.trim().parse().map_err(|_| GpxError::InvalidNumber {
+        element: element.name.clone(),
+        value: text.into(),
+    })
+}
+
+fn parse_waypoint(element: &Element) -> Result<GpxWaypoint, GpxError> {
+    let coordinate = |attribute: &'static str| {
+        let value = element
+            .attribute(attribute)
+            .ok_or(GpxError::MissingAttribute {
+                element: element.name.clone(),
+                attribute,
+            })?;
+        parse_number(element, value)
+    };
+    let location = Location::from(coordinate("lat")?, coordinate("lon")?);
+
+    let elevation = element
+        .child_text("ele")
+        .map(|text| parse_number(element, &text))
+        .transpose()?;
+    let time = element
+        .child_text("time")
+        .map(|text| {
+            Timestamp::parse_rfc3339(&text).map_err(|_| GpxError::InvalidTime { value: text })
+        })
+        .transpose()?;
+
+    Ok(GpxWaypoint {
+        location,
+        elevation,
+        time,
+        name: element.child_text("name"),
+        description: element.child_text("desc"),
+    })
+}
+
+fn parse_points(element: &Element, name: &str) -> Result<Vec<GpxWaypoint>, GpxError> {
+    element.children_named(name).map(parse_waypoint).collect()
+}
+
+impl Gpx {
+    /// Parses a GPX 1.0 or 1.1 document.
+    pub fn parse(input: &str) -> Result<Self, GpxError> {
+        let root = xml::parse(input)?;
+        if root.name != "gpx" {
+            return Err(GpxError::NotGpx { root: root.name });
+        }
+
+        let routes = root
+            .children_named("rte")
+            .map(|route| {
+                Ok(GpxRoute {
+                    name: route.child_text("name"),
+                    description: route.child_text("desc"),
+                    points: parse_points(route, "rtept")?,
+                })
+            })
+            .collect::<Result<_, GpxError>>()?;
+
+        let tracks = root
+            .children_named("trk")
+            .map(|track| {
+                let segments = track
+                    .children_named("trkseg")
+                    .map(|segment| {
+                        Ok(GpxTrackSegment {
+                            points: parse_points(segment, "trkpt")?,
+                        })
+                    })
+                    .collect::<Result<_, GpxError>>()?;
+                Ok(GpxTrack {
+                    name: track.child_text("name"),
+                    description: track.child_text("desc"),
+                    segments,
+                })
+            })
+            .collect::<Result<_, GpxError>>()?;
+
+        Ok(Self {
+            creator: root.attribute("creator").map(String::from),
+            waypoints: parse_points(&root, "wpt")?,
+            routes,
+            tracks,
+        })
+    }
+
+    /// Writes the document as GPX 1.1.
+    pub fn to_gpx_string(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl FromStr for Gpx {
+    type Err = GpxError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        Self::parse(input)
+    }
+}
+
+fn write_text_element(
+    f: &mut impl Write,
+    indent: &str,
+    name: &str,
+    text: &Option<String>,
+) -> fmt::Result {
+    match text {
+        Some(text) => writeln!(f, "{}<{}>{}</{}>", indent, name, xml::escape(text), name),
+        None => Ok(()),
+    }
+}
+
+fn write_waypoint(f: &mut impl Write, indent: &str, tag: &str, point: &GpxWaypoint) -> fmt::Result {
+    writeln!(
+        f,
+        "{}<{} lat=\"{}\" lon=\"{}\">",
+        indent, tag, point.location.latitude, point.location.longitude
+    )?;
+    let inner = format!("{}  ", indent);
+    if let Some(elevation) = point.elevation {
+        writeln!(f, "{}<ele>{}</ele>", inner, elevation)?;
+    }
+    if let Some(time) = point.time {
+        writeln!(f, "{}<time>{}</time>", inner, time)?;
+    }
+    write_text_element(f, &inner, "name", &point.name)?;
+    write_text_element(f, &inner, "desc", &point.description)?;
+    writeln!(f, "{}</{}>", indent, tag)
+}
+
+impl fmt::Display for Gpx {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+        writeln!(
+            f,
+            r#"<gpx version="1.1" creator="{}" xmlns="http://www.topografix.com/GPX/1/1">"#,
+            xml::escape(self.creator.as_deref().unwrap_or("longitude"))
+        )?;
+
+        for waypoint in &self.waypoints {
+            write_waypoint(f, "  ", "wpt", waypoint)?;
+        }
+
+        for route in &self.routes {
+            writeln!(f, "  <rte>")?;
+            write_text_element(f, "    ", "name", &route.name)?;
+            write_text_element(f, "    ", "desc", &route.description)?;
+            for point in &route.points {
+                write_waypoint(f, "    ", "rtept", point)?;
+            }
+            writeln!(f, "  </rte>")?;
+        }
+
+        for track in &self.tracks {
+            writeln!(f, "  <trk>")?;
+            write_text_element(f, "    ", "name", &track.name)?;
+            write_text_element(f, "    ", "desc", &track.description)?;
+            for segment in &track.segments {
+                writeln!(f, "    <trkseg>")?;
+                for point in &segment.points {
+                    write_waypoint(f, "      ", "trkpt", point)?;
+                }
+                writeln!(f, "    </trkseg>")?;
+            }
+            writeln!(f, "  </trk>")?;
+        }
+
+        writeln!(f, "</gpx>")
+    }
+}
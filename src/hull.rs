@@ -0,0 +1,121 @@
+use core::fmt;
+
+use crate::location::Location;
+use crate::sphere::Vector3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HullError {
+    /// The points don't all lie within the hemisphere centred on their mean, so the hull isn't well defined.
+    NotInHemisphere,
+}
+
+impl fmt::Display for HullError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            HullError::NotInHemisphere => write!(f, "points don't fit within a hemisphere"),
+        }
+    }
+}
+
+impl std::error::Error for HullError {}
+
+/// Twice the signed area of the triangle `oab`, positive when it turns counter-clockwise.
+fn cross(o: (f64, f64), a: (f64, f64), b: (f64, f64)) -> f64 {
+    (a.0 - o.0) * (b.1 - o.1) - (a.1 - o.1) * (b.0 - o.0)
+}
+
+/// Andrew's monotone chain. Returns the indices of the hull counter-clockwise without
+/// collinear points. Duplicates collapse, and collinear input gives its two end points.
+fn monotone_chain(points: &[(f64, f64)]) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..points.len()).collect();
+    order.sort_by(|&a, &b| {
+        let (a, b) = (points[a], points[b]);
+        a.0.total_cmp(&b.0).then(a.1.total_cmp(&b.1))
+    });
+    order.dedup_by(|a, b| points[*a] == points[*b]);
+    if order.len() < 3 {
+        return order;
+    }
+
+    let half = |order: &mut dyn Iterator<Item = &usize>| {
+        let mut chain: Vec<usize> = Vec::new();
+        for &index in order {
+            while chain.len() >= 2
+                && cross(
+                    points[chain[chain.len() - 2]],
+                    points[chain[chain.len() - 1]],
+                    points[index],
+                ) <= 1e-12
+            {
+                chain.pop();
+            }
+            chain.push(index);
+        }
+        chain.pop();
+        chain
+    };
+    let mut hull = half(&mut order.iter());
+    hull.extend(half(&mut order.iter().rev()));
+    hull
+}
+
+/// The convex hull of points treated as planar `(longitude, latitude)` coordinates.
+///
+/// Returns an open ring, counter-clockwise, without collinear or duplicate points. Fewer than three
+/// distinct points, or points all on one line, give the distinct end points instead. This is fast and
+/// suits small areas away from the poles; it doesn't handle sets spanning the antimeridian.
+pub fn planar_convex_hull(locations: &[Location]) -> Vec<Location> {
+    let points: Vec<(f64, f64)> = locations
+        .iter()
+        .map(|location| (location.longitude, location.latitude))
+        .collect();
+    monotone_chain(&points)
+        .into_iter()
+        .map(|index| locations[index].clone())
+        .collect()
+}
+
+/// The convex hull on the sphere, with edges along great circles.
+///
+/// The points must lie within the hemisphere centred on their mean. They're projected
+/// gnomonically, which maps great circles to straight lines, so the planar hull of the
+/// projection is the spherical hull. Works across the antimeridian and around the poles.
+/// The result is shaped like `planar_convex_hull`'s, counter-clockwise seen from above.
+pub fn spherical_convex_hull(locations: &[Location]) -> Result<Vec<Location>, HullError> {
+    if locations.is_empty() {
+        return Ok(Vec::new());
+    }
+    let vectors: Vec<Vector3> = locations.iter().map(Vector3::from_location).collect();
+    let centre = vectors
+        .iter()
+        .fold(Vector3::default(), |sum, v| sum.add(v))
+        .normalize()
+        .ok_or(HullError::NotInHemisphere)?;
+    if vectors.iter().any(|v| v.dot(&centre) <= 1e-12) {
+        return Err(HullError::NotInHemisphere);
+    }
+
+    // An orthonormal basis for the plane touching the sphere at the centre
+    let reference = if centre.z.abs() < 0.9 {
+        Vector3::new(0., 0., 1.)
+    } else {
+        Vector3::new(1., 0., 0.)
+    };
+    let east = reference
+        .cross(&centre)
+        .normalize()
+        .ok_or(HullError::NotInHemisphere)?;
+    let north = centre.cross(&east);
+
+    let points: Vec<(f64, f64)> = vectors
+        .iter()
+        .map(|v| {
+            let projected = v.scale(1. / v.dot(&centre));
+            (projected.dot(&east), projected.dot(&north))
+        })
+        .collect();
+    Ok(monotone_chain(&points)
+        .into_iter()
+        .map(|index| locations[index].clone())
+        .collect())
+}
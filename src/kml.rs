@@ -0,0 +1,470 @@
+use core::fmt;
+use core::fmt::Write;
+use core::str::FromStr;
+
+use crate::geometry::{Geometry, LineString, Polygon};
+use crate::location::Location;
+use crate::xml::{self, Element, XmlError};
+
+/// An error produced while reading a KML or KMZ document.
+#[derive(Debug, Clone, PartialEq)]
+pub enum KmlError {
+    Xml(XmlError),
+    /// The root element wasn't `<kml>`.
+    NotKml {
+        root: String,
+    },
+    /// A `<coordinates>` tuple couldn't be parsed as `lon,lat[,alt]`.
+    InvalidCoordinates {
+        value: String,
+    },
+    /// The KMZ archive couldn't be read, didn't contain a `.kml` file, or its `.kml` file was
+    /// too large. Only returned with the `kmz` feature, but always present so the feature is additive.
+    Archive(String),
+}
+
+impl fmt::Display for KmlError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            KmlError::Xml(error) => write!(f, "invalid KML: {}", error),
+            KmlError::NotKml { root } => write!(f, "expected <kml> root, found <{}>", root),
+            KmlError::InvalidCoordinates { value } => {
+                write!(f, "invalid KML coordinates {:?}", value)
+            }
+            KmlError::Archive(message) => write!(f, "invalid KMZ archive: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for KmlError {}
+
+impl From<XmlError> for KmlError {
+    fn from(error: XmlError) -> Self {
+        KmlError::Xml(error)
+    }
+}
+
+/// A KML placemark: a named geometry with optional `<ExtendedData>` properties.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Placemark {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub properties: Vec<(String, String)>,
+    pub geometry: Option<Geometry>,
+}
+
+impl Placemark {
+    pub fn from(geometry: Geometry) -> Self {
+        Self {
+            geometry: Some(geometry),
+            ..Self::default()
+        }
+    }
+
+    pub fn property(&self, name: &str) -> Option<&str> {
+        self.properties
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Kml {
+    pub name: Option<String>,
+    pub placemarks: Vec<Placemark>,
+}
+
+fn parse_coordinates(element: &Element) -> Result<Vec<Location>, KmlError> {
+    let text = element
+        .child("coordinates")
+        .map(Element::text)
+        .unwrap_or_default();
+
+    text.split_whitespace()
+        .map(|tuple| {
+            let mut parts = tuple.split(',').map(|part| part.parse::<f64>());
+            match (parts.next(), parts.next()) {
+                (Some(Ok(longitude)), Some(Ok(latitude))) => {
+                    Ok(Location::from(latitude, longitude))
+                }
+                _ => Err(KmlError::InvalidCoordinates {
+                    value: tuple.into(),
+                }),
+            }
+        })
+        .collect()
+}
+
+fn parse_ring(boundary: &Element) -> Result<LineString, KmlError> {
+    match boundary.child("LinearRing") {
+        Some(ring) => Ok(LineString::from(parse_coordinates(ring)?)),
+        None => Ok(LineString::default()),
+    }
+}
+
+fn parse_polygon(element: &Element) -> Result<Polygon, KmlError> {
+    let exterior = match element.child("outerBoundaryIs") {
+        Some(boundary) => parse_ring(boundary)?,
+        None => LineString::default(),
+    };
+    let interiors = element
+        .children_named("innerBoundaryIs")
+        .map(parse_ring)
+        .collect::<Result<_, _>>()?;
+    Ok(Polygon::from(exterior, interiors))
+}
+
+/// Parses a geometry element. A `<MultiGeometry>` gives one multi geometry for each type of
+/// member it contains, and other elements give nothing.
+fn parse_geometry(element: &Element) -> Result<Vec<Geometry>, KmlError> {
+    let geometry = match element.name.as_str() {
+        "Point" => parse_coordinates(element)?
+            .into_iter()
+            .next()
+            .map(Geometry::Point),
+        "LineString" | "LinearRing" => Some(Geometry::LineString(LineString::from(
+            parse_coordinates(element)?,
+        ))),
+        "Polygon" => Some(Geometry::Polygon(parse_polygon(element)?)),
+        "MultiGeometry" => {
+            let mut members = Vec::new();
+            for child in element.elements() {
+                members.extend(parse_geometry(child)?);
+            }
+            return Ok(collect_multi_geometry(members));
+        }
+        _ => None,
+    };
+    Ok(geometry.into_iter().collect())
+}
+
+/// Groups the members of a `<MultiGeometry>` into a multi point, multi line string and multi
+/// polygon, leaving out the types that don't appear. `Geometry` has no collection type, so a
+/// mixed `<MultiGeometry>` like a Point with a Polygon becomes more than one geometry.
+fn collect_multi_geometry(members: Vec<Geometry>) -> Vec<Geometry> {
+    let (mut points, mut lines, mut polygons) = (Vec::new(), Vec::new(), Vec::new());
+    for member in members {
+        match member {
+            Geometry::Point(point) => points.push(point),
+            Geometry::MultiPoint(more) => points.extend(more),
+            Geometry::LineString(line) => lines.push(line),
+            Geometry::MultiLineString(more) => lines.extend(more),
+            Geometry::Polygon(polygon) => polygons.push(polygon),
+            Geometry::MultiPolygon(more) => polygons.extend(more),
+        }
+    }
+
+    let mut geometries = Vec::new();
+    if !points.is_empty() {
+        geometries.push(Geometry::MultiPoint(points));
+    }
+    if !lines.is_empty() {
+        geometries.push(Geometry::MultiLineString(lines));
+    }
+    if !polygons.is_empty() {
+        geometries.push(Geometry::MultiPolygon(polygons));
+    }
+    if geometries.is_empty() {
+        geometries.push(Geometry::MultiPoint(vec![]));
+    }
+    geometries
+}
+
+/// Parses a placemark. A mixed `<MultiGeometry>` gives one placemark per geometry type, each
+/// with the same name, description and properties.
+fn parse_placemark(element: &Element) -> Result<Vec<Placemark>, KmlError> {
+    let mut geometries = Vec::new();
+    for child in element.elements() {
+        geometries = parse_geometry(child)?;
+        if !geometries.is_empty() {
+            break;
+        }
+    }
+
+    let properties = element
+        .child("ExtendedData")
+        .map(|data| {
+            data.children_named("Data")
+                .filter_map(|item| {
+                    let name = item.attribute("name")?;
+                    Some((name.into(), item.child_text("value").unwrap_or_default()))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let placemark = Placemark {
+        name: element.child_text("name"),
+        description: element.child_text("description"),
+        properties,
+        geometry: None,
+    };
+    if geometries.is_empty() {
+        return Ok(vec![placemark]);
+    }
+    Ok(geometries
+        .into_iter()
+        .map(|geometry| Placemark {
+            geometry: Some(geometry),
+            ..placemark.clone()
+        })
+        .collect())
+}
+
+impl Kml {
+    /// Parses a KML 2.2 document, collecting placemarks from every folder.
+    ///
+    /// A placemark whose `<MultiGeometry>` mixes points, lines and polygons is split into one
+    /// placemark per geometry type.
+    pub fn parse(input: &str) -> Result<Self, KmlError> {
+        let root = xml::parse(input)?;
+        if root.name != "kml" {
+            return Err(KmlError::NotKml { root: root.name });
+        }
+
+        let mut elements = Vec::new();
+        root.descendants_named("Placemark", &mut elements);
+        let mut placemarks = Vec::new();
+        for element in elements {
+            placemarks.extend(parse_placemark(element)?);
+        }
+
+        let name = root
+            .elements()
+            .find(|element| element.name == "Document" || element.name == "Folder")
+            .and_then(|document| document.child_text("name"));
+
+        Ok(Self { name, placemarks })
+    }
+
+    /// Writes the document as KML 2.2.
+    pub fn to_kml_string(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl FromStr for Kml {
+    type Err = KmlError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        Self::parse(input)
+    }
+}
+
+fn write_coordinates(f: &mut impl Write, indent: &str, points: &[Location]) -> fmt::Result {
+    write!(f, "{}<coordinates>", indent)?;
+    for (i, point) in points.iter().enumerate() {
+        if i > 0 {
+            write!(f, " ")?;
+        }
+        write!(f, "{},{}", point.longitude, point.latitude)?;
+    }
+    writeln!(f, "</coordinates>")
+}
+
+fn write_polygon(f: &mut impl Write, indent: &str, polygon: &Polygon) -> fmt::Result {
+    writeln!(f, "{}<Polygon>", indent)?;
+    for (i, ring) in polygon.rings().enumerate() {
+        let boundary = if i == 0 {
+            "outerBoundaryIs"
+        } else {
+            "innerBoundaryIs"
+        };
+        writeln!(f, "{}  <{}>", indent, boundary)?;
+        writeln!(f, "{}    <LinearRing>", indent)?;
+        write_coordinates(f, &format!("{}      ", indent), &ring.closed_points())?;
+        writeln!(f, "{}    </LinearRing>", indent)?;
+        writeln!(f, "{}  </{}>", indent, boundary)?;
+    }
+    writeln!(f, "{}</Polygon>", indent)
+}
+
+fn write_geometry(f: &mut impl Write, indent: &str, geometry: &Geometry) -> fmt::Result {
+    let inner = format!("{}  ", indent);
+    match geometry {
+        Geometry::Point(point) => {
+            writeln!(f, "{}<Point>", indent)?;
+            write_coordinates(f, &inner, core::slice::from_ref(point))?;
+            writeln!(f, "{}</Point>", indent)
+        }
+        Geometry::LineString(line) => {
+            writeln!(f, "{}<LineString>", indent)?;
+            write_coordinates(f, &inner, &line.points)?;
+            writeln!(f, "{}</LineString>", indent)
+        }
+        Geometry::Polygon(polygon) => write_polygon(f, indent, polygon),
+        Geometry::MultiPoint(points) => {
+            writeln!(f, "{}<MultiGeometry>", indent)?;
+            for point in points {
+                write_geometry(f, &inner, &Geometry::Point(point.clone()))?;
+            }
+            writeln!(f, "{}</MultiGeometry>", indent)
+        }
+        Geometry::MultiLineString(lines) => {
+            writeln!(f, "{}<MultiGeometry>", indent)?;
+            for line in lines {
+                writeln!(f, "{}<LineString>", inner)?;
+                write_coordinates(f, &format!("{}  ", inner), &line.points)?;
+                writeln!(f, "{}</LineString>", inner)?;
+            }
+            writeln!(f, "{}</MultiGeometry>", indent)
+        }
+        Geometry::MultiPolygon(polygons) => {
+            writeln!(f, "{}<MultiGeometry>", indent)?;
+            for polygon in polygons {
+                write_polygon(f, &inner, polygon)?;
+            }
+            writeln!(f, "{}</MultiGeometry>", indent)
+        }
+    }
+}
+
+impl fmt::Display for Kml {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+        writeln!(f, r#"<kml xmlns="http://www.opengis.net/kml/2.2">"#)?;
+        writeln!(f, "  <Document>")?;
+        if let Some(name) = &self.name {
+            writeln!(f, "    <name>{}</name>", xml::escape(name))?;
+        }
+
+        for placemark in &self.placemarks {
+            writeln!(f, "    <Placemark>")?;
+            if let Some(name) = &placemark.name {
+                writeln!(f, "      <name>{}</name>", xml::escape(name))?;
+            }
+            if let Some(description) = &placemark.description {
+                writeln!(
+                    f,
+                    "      <description>{}</description>",
+                    xml::escape(description)
+                )?;
+            }
+            if !placemark.properties.is_empty() {
+                writeln!(f, "      <ExtendedData>")?;
+                for (name, value) in &placemark.properties {
+                    writeln!(
+                        f,
+                        "        <Data name=\"{}\"><value>{}</value></Data>",
+                        xml::escape(name),
+                        xml::escape(value)
+                    )?;
+                }
+                writeln!(f, "      </ExtendedData>")?;
+            }
+            if let Some(geometry) = &placemark.geometry {
+                write_geometry(f, "      ", geometry)?;
+            }
+            writeln!(f, "    </Placemark>")?;
+        }
+
+        writeln!(f, "  </Document>")?;
+        writeln!(f, "</kml>")
+    }
+}
+
+/// The largest `.kml` file read from a KMZ archive, so a zip bomb can't exhaust memory.
+#[cfg(feature = "kmz")]
+const MAX_KML_BYTES: u64 = if cfg!(test) { 1 << 20 } else { 256 << 20 };
+
+#[cfg(feature = "kmz")]
+impl Kml {
+    /// Reads a KMZ archive, parsing `doc.kml` or else the first `.kml` file inside it.
+    /// KML files over 256 MiB once decompressed are rejected.
+    pub fn from_kmz(bytes: &[u8]) -> Result<Self, KmlError> {
+        use std::io::{Cursor, Read};
+
+        let archive_error = |error: zip::result::ZipError| KmlError::Archive(error.to_string());
+        let mut archive = zip::ZipArchive::new(Cursor::new(bytes)).map_err(archive_error)?;
+
+        let name = archive
+            .file_names()
+            .filter(|name| name.to_ascii_lowercase().ends_with(".kml"))
+            .min_by_key(|name| *name != "doc.kml")
+            .map(String::from)
+            .ok_or_else(|| KmlError::Archive("no .kml file in archive".into()))?;
+
+        let mut text = String::new();
+        archive
+            .by_name(&name)
+            .map_err(archive_error)?
+            .take(MAX_KML_BYTES + 1)
+            .read_to_string(&mut text)
+            .map_err(|error| KmlError::Archive(error.to_string()))?;
+        if text.len() as u64 > MAX_KML_BYTES {
+            return Err(KmlError::Archive(format!(
+                "{} is larger than {} bytes",
+                name, MAX_KML_BYTES
+            )));
+        }
+
+        Self::parse(&text)
+    }
+
+    /// Writes the document as a KMZ archive containing a single deflated `doc.kml`.
+    pub fn to_kmz(&self) -> Vec<u8> {
+        use std::io::{Cursor, Write};
+
+        let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        let options =
+            zip::write::FileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+        // Writing to an in-memory buffer can't fail
+        writer
+            .start_file("doc.kml", options)
+            .expect("in-memory zip write");
+        writer
+            .write_all(self.to_kml_string().as_bytes())
+            .expect("in-memory zip write");
+        writer.finish().expect("in-memory zip write").into_inner()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl From<Placemark> for crate::geojson::Feature {
+    /// Converts a placemark into a GeoJSON feature, storing the name, description
+    /// and extended data as properties.
+    fn from(placemark: Placemark) -> Self {
+        let mut properties = serde_json::Map::new();
+        if let Some(name) = placemark.name {
+            properties.insert("name".into(), name.into());
+        }
+        if let Some(description) = placemark.description {
+            properties.insert("description".into(), description.into());
+        }
+        for (key, value) in placemark.properties {
+            properties.insert(key, value.into());
+        }
+
+        Self {
+            id: None,
+            geometry: placemark.geometry,
+            properties,
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl From<crate::geojson::Feature> for Placemark {
+    /// Converts a GeoJSON feature into a placemark. The `name` and `description`
+    /// properties become the placemark's name and description and the remaining
+    /// properties become extended data.
+    fn from(feature: crate::geojson::Feature) -> Self {
+        let mut placemark = Placemark {
+            geometry: feature.geometry,
+            ..Placemark::default()
+        };
+        for (key, value) in feature.properties {
+            let value = match value {
+                serde_json::Value::String(text) => text,
+                other => other.to_string(),
+            };
+            match key.as_str() {
+                "name" => placemark.name = Some(value),
+                "description" => placemark.description = Some(value),
+                _ => placemark.properties.push((key, value)),
+            }
+        }
+        placemark
+    }
+}
@@ -1,14 +1,111 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
+#[cfg(feature = "std")]
+mod ball_tree;
+mod bounding_box;
+#[cfg(feature = "std")]
+mod buffer;
+mod cell;
+mod circle;
+#[cfg(feature = "std")]
+mod clean;
+#[cfg(feature = "std")]
+mod cluster;
+#[cfg(feature = "std")]
+mod csv;
+mod dms;
+#[cfg(feature = "std")]
+mod enclosing;
+mod error;
+mod format;
+mod geofence;
+#[cfg(all(feature = "serde", feature = "std"))]
+mod geojson;
+#[cfg(feature = "std")]
+mod geometry;
+#[cfg(feature = "std")]
+mod gpx;
+#[cfg(feature = "std")]
+mod hull;
+#[cfg(feature = "std")]
+mod kml;
 mod location;
 mod measurement;
-
-pub use location::{find_center_point, Direction, Location};
-pub use measurement::{Distance, DistanceUnit};
+mod nmea;
+#[cfg(feature = "std")]
+mod polyline;
+#[cfg(feature = "std")]
+mod rtree;
+mod speed;
+mod sphere;
+#[cfg(feature = "std")]
+mod stops;
+mod time;
+#[cfg(feature = "std")]
+mod track;
+#[cfg(feature = "std")]
+mod wkb;
+#[cfg(feature = "std")]
+mod wkt;
+#[cfg(feature = "std")]
+mod xml;
+
+#[cfg(feature = "std")]
+pub use ball_tree::BallTree;
+pub use bounding_box::BoundingBox;
+pub use cell::{CellId, MAX_CELL_LEVEL};
+pub use circle::Circle;
+#[cfg(feature = "std")]
+pub use cluster::{dbscan, grid_clusters, Cluster, Clustering};
+#[cfg(feature = "std")]
+pub use csv::{
+    CoordinateFormat, CsvColumn, CsvError, CsvOptions, CsvReader, CsvRecord, CsvWriter, InvalidRows,
+};
+pub use dms::{parse_dms, Axis, Dms, DmsError};
+pub use error::Error;
+pub use format::{DistanceFormat, FormattedDistance, UnitSystem};
+pub use geofence::{FenceState, Geofence, GeofenceEvent, GeofenceEventKind, GeofenceMonitor};
+#[cfg(all(feature = "serde", feature = "std"))]
+pub use geojson::{Feature, FeatureCollection, GeoJsonError};
+#[cfg(feature = "std")]
+pub use geometry::{Geometry, LineString, Polygon};
+#[cfg(feature = "std")]
+pub use gpx::{Gpx, GpxError, GpxRoute, GpxTrack, GpxTrackSegment, GpxWaypoint};
+#[cfg(feature = "std")]
+pub use hull::{planar_convex_hull, spherical_convex_hull, HullError};
+#[cfg(feature = "std")]
+pub use kml::{Kml, KmlError, Placemark};
+pub use location::{find_center_point, Direction, Location, LocationError};
+pub use measurement::{
+    Distance, DistanceError, DistanceUnit, OrderedDistance, ParseDistanceError, Tolerance,
+};
+pub use nmea::{
+    parse_nmea, FixQuality, FixType, Gga, Gll, Gsa, NmeaDate, NmeaError, NmeaSentence, NmeaTime,
+    Rmc, Vtg,
+};
+#[cfg(feature = "std")]
+pub use polyline::{decode_polyline, encode_polyline, PolylineError};
+#[cfg(feature = "std")]
+pub use rtree::{PackedRTree, RTree};
+pub use speed::{ParseSpeedError, Speed, SpeedUnit};
+#[cfg(feature = "std")]
+pub use stops::Stop;
+pub use time::{Timestamp, TimestampError};
+#[cfg(feature = "std")]
+pub use track::{Split, Track, TrackPoint};
+#[cfg(feature = "std")]
+pub use wkb::{ByteOrder, WkbError};
+#[cfg(feature = "std")]
+pub use wkt::WktError;
+#[cfg(feature = "std")]
+pub use xml::XmlError;
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    #[cfg(feature = "std")]
+    use crate::sphere::{angle_to_arc, arc_length, ring_contains, Vector3};
+    use core::time::Duration;
 
     // ==================== Distance Unit Tests ====================
 
@@ -539,4 +636,3209 @@ mod tests {
         let loc3 = Location::from_normalized(100.0, 0.0);
         assert!(loc3.latitude >= -90.0 && loc3.latitude <= 90.0);
     }
+
+    // ==================== Polyline Tests ====================
+
+    #[cfg(feature = "std")]
+    fn google_example_route() -> [Location; 3] {
+        [
+            Location::from(38.5, -120.2),
+            Location::from(40.7, -120.95),
+            Location::from(43.252, -126.453),
+        ]
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn polyline_encode_google_example() {
+        let encoded = encode_polyline(&google_example_route(), 5);
+        assert_eq!(encoded, "_p~iF~ps|U_ulLnnqC_mqNvxq`@");
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn polyline_decode_google_example() {
+        let decoded = decode_polyline("_p~iF~ps|U_ulLnnqC_mqNvxq`@", 5).unwrap();
+        let expected = google_example_route();
+
+        assert_eq!(decoded.len(), expected.len());
+        for (a, b) in decoded.iter().zip(expected.iter()) {
+            assert!((a.latitude - b.latitude).abs() < 0.000001);
+            assert!((a.longitude - b.longitude).abs() < 0.000001);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn polyline_round_trip_precision_6() {
+        let locations = [
+            Location::from(40.7885447, -111.7656248),
+            Location::from(40.7945846, -111.6950349),
+            Location::from(-33.865143, 151.2099),
+        ];
+        let encoded = encode_polyline(&locations, 6);
+        let decoded = decode_polyline(&encoded, 6).unwrap();
+
+        for (a, b) in decoded.iter().zip(locations.iter()) {
+            assert!((a.latitude - b.latitude).abs() < 0.000001);
+            assert!((a.longitude - b.longitude).abs() < 0.000001);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn polyline_empty() {
+        assert_eq!(encode_polyline(&[], 5), "");
+        assert!(decode_polyline("", 5).unwrap().is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn polyline_truncated_value() {
+        // The last character still has the continuation bit set
+        let result = decode_polyline("_p~iF~ps|U_", 5);
+        assert_eq!(result, Err(PolylineError::Truncated { position: 11 }));
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn polyline_missing_longitude() {
+        let result = decode_polyline("_p~iF", 5);
+        assert_eq!(result, Err(PolylineError::Truncated { position: 5 }));
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn polyline_invalid_character() {
+        let result = decode_polyline("_p~iF ps|U", 5);
+        assert_eq!(
+            result,
+            Err(PolylineError::InvalidCharacter {
+                character: ' ',
+                position: 5
+            })
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn polyline_value_overflow() {
+        let result = decode_polyline(&"~~~~~~~~~~~~^".repeat(4), 5);
+        assert_eq!(result, Err(PolylineError::Overflow { position: 12 }));
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn polyline_total_overflow() {
+        // Two latitude deltas of 2^62 add up past i64::MAX
+        let result = decode_polyline("____________G?____________G?", 5);
+        assert_eq!(result, Err(PolylineError::Overflow { position: 14 }));
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn polyline_encode_extreme_coordinates() {
+        let locations = [
+            Location::from(f64::INFINITY, f64::NAN),
+            Location::from(f64::NEG_INFINITY, 1e300),
+        ];
+        let encoded = encode_polyline(&locations, 30);
+        let decoded = decode_polyline(&encoded, 30).unwrap();
+        assert_eq!(decoded.len(), 2);
+        assert_eq!(decoded[0].longitude, 0.);
+    }
+
+    // ==================== GeoJSON Tests ====================
+
+    #[test]
+    #[cfg(all(feature = "serde", feature = "std"))]
+    fn geojson_point_uses_lng_lat_order() {
+        let point = Geometry::Point(Location::from(40.78, -111.76));
+        let value = point.to_geojson();
+
+        assert_eq!(value["type"], "Point");
+        assert_eq!(value["coordinates"][0], -111.76);
+        assert_eq!(value["coordinates"][1], 40.78);
+    }
+
+    #[test]
+    #[cfg(all(feature = "serde", feature = "std"))]
+    fn geojson_parse_line_string() {
+        let input =
+            r#"{"type":"LineString","coordinates":[[-111.76,40.78],[-111.69,40.79,1500.0]]}"#;
+        let geometry = Geometry::from_geojson_str(input).unwrap();
+
+        let expected = LineString::from(vec![
+            Location::from(40.78, -111.76),
+            Location::from(40.79, -111.69),
+        ]);
+        assert_eq!(geometry, Geometry::LineString(expected));
+    }
+
+    #[test]
+    #[cfg(all(feature = "serde", feature = "std"))]
+    fn geojson_polygon_rings_are_closed() {
+        let exterior = LineString::from(vec![
+            Location::from(0.0, 0.0),
+            Location::from(0.0, 10.0),
+            Location::from(10.0, 10.0),
+        ]);
+        let polygon = Geometry::Polygon(Polygon::from(exterior, vec![]));
+        let value = polygon.to_geojson();
+
+        let ring = value["coordinates"][0].as_array().unwrap();
+        assert_eq!(ring.len(), 4);
+        assert_eq!(ring[0], ring[3]);
+    }
+
+    #[test]
+    #[cfg(all(feature = "serde", feature = "std"))]
+    fn geojson_round_trip_all_geometries() {
+        let ring = LineString::from(vec![
+            Location::from(0.0, 0.0),
+            Location::from(0.0, 10.0),
+            Location::from(10.0, 10.0),
+            Location::from(0.0, 0.0),
+        ]);
+        let hole = LineString::from(vec![
+            Location::from(1.0, 1.0),
+            Location::from(1.0, 2.0),
+            Location::from(2.0, 2.0),
+            Location::from(1.0, 1.0),
+        ]);
+        let polygon = Polygon::from(ring.clone(), vec![hole]);
+        let geometries = vec![
+            Geometry::Point(Location::from(1.5, 2.5)),
+            Geometry::MultiPoint(vec![Location::from(1.0, 2.0), Location::from(3.0, 4.0)]),
+            Geometry::LineString(ring.clone()),
+            Geometry::MultiLineString(vec![ring.clone(), ring]),
+            Geometry::Polygon(polygon.clone()),
+            Geometry::MultiPolygon(vec![polygon.clone(), polygon]),
+        ];
+
+        for geometry in geometries {
+            let text = serde_json::to_string(&geometry).unwrap();
+            let parsed: Geometry = serde_json::from_str(&text).unwrap();
+            assert_eq!(parsed, geometry);
+        }
+    }
+
+    #[test]
+    #[cfg(all(feature = "serde", feature = "std"))]
+    fn geojson_feature_collection_with_properties() {
+        let feature = Feature::from(Geometry::Point(Location::from(40.78, -111.76)))
+            .with_property("name", "Trailhead")
+            .with_property("elevation", 1500);
+        let collection = FeatureCollection::from(vec![feature]);
+
+        let text = collection.to_geojson_string();
+        let parsed = FeatureCollection::from_geojson_str(&text).unwrap();
+
+        assert_eq!(parsed, collection);
+        assert_eq!(parsed.features[0].properties["name"], "Trailhead");
+    }
+
+    #[test]
+    #[cfg(all(feature = "serde", feature = "std"))]
+    fn geojson_feature_without_geometry() {
+        let input = r#"{"type":"Feature","id":7,"geometry":null,"properties":null}"#;
+        let feature = Feature::from_geojson_str(input).unwrap();
+
+        assert_eq!(feature.id, Some(serde_json::json!(7)));
+        assert!(feature.geometry.is_none());
+        assert!(feature.properties.is_empty());
+    }
+
+    #[test]
+    #[cfg(all(feature = "serde", feature = "std"))]
+    fn geojson_errors() {
+        assert_eq!(
+            Geometry::from_geojson_str(r#"{"coordinates":[1,2]}"#),
+            Err(GeoJsonError::MissingMember("type"))
+        );
+        assert_eq!(
+            Geometry::from_geojson_str(r#"{"type":"Point","coordinates":[1]}"#),
+            Err(GeoJsonError::InvalidCoordinates { geometry: "Point" })
+        );
+        assert!(matches!(
+            Feature::from_geojson_str(r#"{"type":"Point","coordinates":[1,2]}"#),
+            Err(GeoJsonError::UnexpectedType { .. })
+        ));
+        assert!(matches!(
+            Geometry::from_geojson_str("{"),
+            Err(GeoJsonError::Json(_))
+        ));
+    }
+
+    // ==================== WKT Tests ====================
+
+    #[cfg(feature = "std")]
+    fn square_with_hole() -> Polygon {
+        let exterior = LineString::from(vec![
+            Location::from(0.0, 0.0),
+            Location::from(0.0, 10.0),
+            Location::from(10.0, 10.0),
+            Location::from(10.0, 0.0),
+            Location::from(0.0, 0.0),
+        ]);
+        let hole = LineString::from(vec![
+            Location::from(2.0, 2.0),
+            Location::from(2.0, 3.0),
+            Location::from(3.0, 3.0),
+            Location::from(2.0, 2.0),
+        ]);
+        Polygon::from(exterior, vec![hole])
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn wkt_point() {
+        let location = Location::from(40.78, -111.76);
+        assert_eq!(location.to_wkt(), "POINT(-111.76 40.78)");
+        assert_eq!(
+            Location::from_wkt("POINT(-111.76 40.78)").unwrap(),
+            location
+        );
+        assert_eq!(
+            Location::from_wkt("point z ( -111.76 40.78 1500 )").unwrap(),
+            location
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn wkt_line_string_and_polygon() {
+        let line: Geometry = "LINESTRING (30 10, 10 30, 40 40)".parse().unwrap();
+        assert_eq!(
+            line,
+            Geometry::LineString(LineString::from(vec![
+                Location::from(10.0, 30.0),
+                Location::from(30.0, 10.0),
+                Location::from(40.0, 40.0),
+            ]))
+        );
+
+        let polygon = Geometry::Polygon(square_with_hole());
+        assert_eq!(
+            polygon.to_wkt(),
+            "POLYGON((0 0,10 0,10 10,0 10,0 0),(2 2,3 2,3 3,2 2))"
+        );
+        assert_eq!(Geometry::from_wkt(&polygon.to_wkt()).unwrap(), polygon);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn wkt_multi_geometries() {
+        let both_styles = ["MULTIPOINT ((10 40), (40 30))", "MULTIPOINT (10 40, 40 30)"];
+        for input in both_styles {
+            assert_eq!(
+                Geometry::from_wkt(input).unwrap(),
+                Geometry::MultiPoint(vec![Location::from(40.0, 10.0), Location::from(30.0, 40.0)])
+            );
+        }
+
+        let multi_polygon = Geometry::MultiPolygon(vec![square_with_hole(), square_with_hole()]);
+        assert_eq!(
+            Geometry::from_wkt(&multi_polygon.to_wkt()).unwrap(),
+            multi_polygon
+        );
+
+        let lines = Geometry::from_wkt("MULTILINESTRING ((10 10, 20 20), (40 40, 30 30))").unwrap();
+        assert!(matches!(lines, Geometry::MultiLineString(ref l) if l.len() == 2));
+        assert_eq!(
+            Geometry::from_wkt("MULTIPOLYGON EMPTY").unwrap(),
+            Geometry::MultiPolygon(vec![])
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn wkt_empty_members_round_trip() {
+        let lines = Geometry::MultiLineString(vec![
+            LineString::from(vec![Location::from(10.0, 10.0), Location::from(20.0, 20.0)]),
+            LineString::default(),
+        ]);
+        assert_eq!(lines.to_wkt(), "MULTILINESTRING((10 10,20 20),EMPTY)");
+        assert_eq!(Geometry::from_wkt(&lines.to_wkt()).unwrap(), lines);
+
+        let polygons = Geometry::MultiPolygon(vec![Polygon::default(), square_with_hole()]);
+        assert_eq!(Geometry::from_wkt(&polygons.to_wkt()).unwrap(), polygons);
+        assert_eq!(
+            Geometry::LineString(LineString::default()).to_wkt(),
+            "LINESTRING EMPTY"
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn wkt_srid_prefix() {
+        let (geometry, srid) = Geometry::from_ewkt("SRID=4326;POINT(-111.76 40.78)").unwrap();
+        assert_eq!(srid, Some(4326));
+        assert_eq!(geometry.to_ewkt(4326), "SRID=4326;POINT(-111.76 40.78)");
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn wkt_errors() {
+        assert_eq!(
+            Geometry::from_wkt("CIRCLE(1 2)"),
+            Err(WktError::UnknownGeometry {
+                name: "CIRCLE".into(),
+                position: 0
+            })
+        );
+        assert_eq!(
+            Geometry::from_wkt("POINT(1 2"),
+            Err(WktError::UnexpectedEnd { expected: "')'" })
+        );
+        assert_eq!(
+            Geometry::from_wkt("POINT(1 x)"),
+            Err(WktError::UnexpectedToken {
+                expected: "number",
+                position: 8
+            })
+        );
+        assert_eq!(
+            Geometry::from_wkt("POINT(1 2) extra"),
+            Err(WktError::UnexpectedToken {
+                expected: "end of input",
+                position: 11
+            })
+        );
+        assert_eq!(
+            Geometry::from_wkt("POINT EMPTY"),
+            Err(WktError::EmptyPoint { position: 0 })
+        );
+    }
+
+    // ==================== WKB Tests ====================
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn wkb_point_little_endian() {
+        let point = Geometry::Point(Location::from(2.0, 1.0));
+        let hex = point.to_ewkb_hex(ByteOrder::LittleEndian, None);
+        assert_eq!(hex, "0101000000000000000000F03F0000000000000040");
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn wkb_postgis_ewkb_with_srid() {
+        // SELECT ST_AsEWKB('SRID=4326;POINT(-111.76 40.78)'::geometry)
+        let ewkb = Geometry::Point(Location::from(40.78, -111.76))
+            .to_ewkb_hex(ByteOrder::LittleEndian, Some(4326));
+        assert!(ewkb.starts_with("0101000020E6100000"));
+
+        let (geometry, srid) = Geometry::from_ewkb_hex(&ewkb).unwrap();
+        assert_eq!(srid, Some(4326));
+        assert_eq!(geometry, Geometry::Point(Location::from(40.78, -111.76)));
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn wkb_round_trip_both_byte_orders() {
+        let geometries = vec![
+            Geometry::LineString(square_with_hole().exterior),
+            Geometry::Polygon(square_with_hole()),
+            Geometry::MultiPoint(vec![Location::from(1.0, 2.0), Location::from(3.0, 4.0)]),
+            Geometry::MultiLineString(vec![square_with_hole().exterior]),
+            Geometry::MultiPolygon(vec![square_with_hole(), square_with_hole()]),
+        ];
+
+        for geometry in geometries {
+            for order in [ByteOrder::BigEndian, ByteOrder::LittleEndian] {
+                let bytes = geometry.to_ewkb(order, Some(3857));
+                assert_eq!(
+                    Geometry::from_ewkb(&bytes).unwrap(),
+                    (geometry.clone(), Some(3857))
+                );
+                assert_eq!(
+                    Geometry::from_wkb(&geometry.to_wkb(order)).unwrap(),
+                    geometry
+                );
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn wkb_skips_z_ordinates() {
+        // ISO WKB POINT Z (1 2 3), big endian
+        let mut bytes = vec![0, 0, 0, 0x03, 0xE9];
+        for value in [1.0f64, 2.0, 3.0] {
+            bytes.extend_from_slice(&value.to_be_bytes());
+        }
+        assert_eq!(
+            Geometry::from_wkb(&bytes).unwrap(),
+            Geometry::Point(Location::from(2.0, 1.0))
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn wkb_errors() {
+        let bytes = Geometry::Point(Location::from(2.0, 1.0)).to_wkb(ByteOrder::LittleEndian);
+
+        assert_eq!(
+            Geometry::from_wkb(&bytes[..10]),
+            Err(WkbError::UnexpectedEnd { offset: 10 })
+        );
+        assert_eq!(
+            Geometry::from_wkb(&[7, 1, 0, 0, 0]),
+            Err(WkbError::InvalidByteOrder { byte: 7, offset: 0 })
+        );
+        assert_eq!(
+            Geometry::from_wkb(&[1, 9, 0, 0, 0]),
+            Err(WkbError::UnknownGeometryType { code: 9, offset: 0 })
+        );
+
+        let mut trailing = bytes.clone();
+        trailing.push(0);
+        assert_eq!(
+            Geometry::from_wkb(&trailing),
+            Err(WkbError::TrailingBytes { offset: 21 })
+        );
+        assert_eq!(
+            Geometry::from_ewkb_hex("01G1"),
+            Err(WkbError::InvalidHex { position: 2 })
+        );
+        assert_eq!(
+            Geometry::from_ewkb_hex("+1"),
+            Err(WkbError::InvalidHex { position: 0 })
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn wkb_nested_members_are_rejected_before_reading() {
+        // MultiPoint headers with a count of 1, each nested in the one before
+        let nested: Vec<u8> = [1, 4, 0, 0, 0, 1, 0, 0, 0].repeat(200_000);
+        assert_eq!(
+            Geometry::from_wkb(&nested),
+            Err(WkbError::UnexpectedMember { code: 4, offset: 9 })
+        );
+    }
+
+    // ==================== Timestamp Tests ====================
+
+    #[test]
+    fn timestamp_parse_utc() {
+        let time = Timestamp::parse_rfc3339("2024-05-01T12:30:15Z").unwrap();
+        assert_eq!(time.unix_millis(), 1_714_566_615_000);
+        assert_eq!(time, Timestamp::from_utc(2024, 5, 1, 12, 30, 15.));
+    }
+
+    #[test]
+    fn timestamp_parse_offset_and_fraction() {
+        let time = Timestamp::parse_rfc3339("2024-05-01T14:30:15.250+02:00").unwrap();
+        let expected = Timestamp::parse_rfc3339("2024-05-01T12:30:15.25Z").unwrap();
+        assert_eq!(time, expected);
+        assert_eq!(time.unix_millis() % 1000, 250);
+    }
+
+    #[test]
+    fn timestamp_before_epoch() {
+        let time = Timestamp::parse_rfc3339("1969-12-31T23:59:59Z").unwrap();
+        assert_eq!(time.unix_millis(), -1000);
+        assert_eq!(time.to_utc(), (1969, 12, 31, 23, 59, 59, 0));
+    }
+
+    #[test]
+    fn timestamp_arithmetic_saturates() {
+        let latest = Timestamp::from_unix_millis(i64::MAX);
+        let earliest = Timestamp::from_unix_millis(i64::MIN);
+        assert_eq!(latest.add_seconds(f64::INFINITY), latest);
+        assert_eq!(earliest.add_seconds(f64::NEG_INFINITY), earliest);
+        assert_eq!(latest.add_seconds(1.), latest);
+        assert_eq!(latest.seconds_since(&earliest), i64::MAX as f64 / 1000.);
+    }
+
+    #[test]
+    fn timestamp_seconds_since() {
+        let start = Timestamp::from_utc(2024, 2, 28, 23, 0, 0.);
+        let end = Timestamp::from_utc(2024, 2, 29, 1, 0, 0.);
+        assert_eq!(end.seconds_since(&start), 7200.);
+        assert_eq!(start.seconds_since(&end), -7200.);
+        assert_eq!(start.add_seconds(7200.), end);
+    }
+
+    #[test]
+    fn timestamp_errors() {
+        assert_eq!(
+            Timestamp::parse_rfc3339("2024-05-01 12:30"),
+            Err(TimestampError::InvalidFormat { position: 16 })
+        );
+        assert_eq!(
+            Timestamp::parse_rfc3339("2023-02-29T00:00:00Z"),
+            Err(TimestampError::OutOfRange { position: 8 })
+        );
+        assert_eq!(
+            Timestamp::parse_rfc3339("2024-05-01T12:30:00"),
+            Err(TimestampError::InvalidFormat { position: 19 })
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn timestamp_display_round_trip() {
+        let time = Timestamp::from_utc(2024, 5, 1, 12, 30, 15.5);
+        assert_eq!(time.to_string(), "2024-05-01T12:30:15.500Z");
+        assert_eq!(time.to_string().parse::<Timestamp>().unwrap(), time);
+        assert_eq!(
+            Timestamp::from_unix_millis(0).to_string(),
+            "1970-01-01T00:00:00Z"
+        );
+    }
+
+    // ==================== GPX Tests ====================
+
+    #[cfg(feature = "std")]
+    const SAMPLE_GPX: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!-- exported from a watch -->
+<gpx version="1.1" creator="Test &amp; Co" xmlns="http://www.topografix.com/GPX/1/1">
+  <wpt lat="40.7885447" lon="-111.7656248">
+    <ele>1500.5</ele>
+    <name>Trailhead</name>
+    <desc><![CDATA[Parking <lot>]]></desc>
+  </wpt>
+  <rte>
+    <name>Approach</name>
+    <rtept lat="40.7885447" lon="-111.7656248"/>
+    <rtept lat="40.7945846" lon="-111.6950349"/>
+  </rte>
+  <trk>
+    <name>Morning run</name>
+    <trkseg>
+      <trkpt lat="40.7885447" lon="-111.7656248">
+        <ele>1500</ele>
+        <time>2024-05-01T12:00:00Z</time>
+      </trkpt>
+      <trkpt lat="40.7945846" lon="-111.6950349">
+        <ele>1550</ele>
+        <time>2024-05-01T12:30:00Z</time>
+      </trkpt>
+    </trkseg>
+  </trk>
+</gpx>"#;
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn gpx_parse_sample() {
+        let gpx: Gpx = SAMPLE_GPX.parse().unwrap();
+
+        assert_eq!(gpx.creator.as_deref(), Some("Test & Co"));
+        assert_eq!(gpx.waypoints.len(), 1);
+        assert_eq!(gpx.waypoints[0].name.as_deref(), Some("Trailhead"));
+        assert_eq!(
+            gpx.waypoints[0].description.as_deref(),
+            Some("Parking <lot>")
+        );
+        assert_eq!(gpx.waypoints[0].elevation, Some(1500.5));
+
+        assert_eq!(gpx.routes[0].name.as_deref(), Some("Approach"));
+        assert_eq!(gpx.routes[0].points.len(), 2);
+
+        let segment = &gpx.tracks[0].segments[0];
+        assert_eq!(segment.points.len(), 2);
+        assert_eq!(
+            segment.points[1].time,
+            Some(Timestamp::from_utc(2024, 5, 1, 12, 30, 0.))
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn gpx_track_distance_uses_location_distance() {
+        let gpx = Gpx::parse(SAMPLE_GPX).unwrap();
+        let expected = Location::from(40.7885447, -111.7656248)
+            .distance(&Location::from(40.7945846, -111.6950349));
+
+        assert!(gpx.tracks[0].distance() == expected);
+        assert!(gpx.routes[0].distance() == expected);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn gpx_round_trip() {
+        let gpx = Gpx::parse(SAMPLE_GPX).unwrap();
+        let written = gpx.to_gpx_string();
+
+        assert!(written.contains(r#"<gpx version="1.1" creator="Test &amp; Co""#));
+        assert!(written.contains("<time>2024-05-01T12:30:00Z</time>"));
+        assert_eq!(Gpx::parse(&written).unwrap(), gpx);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn gpx_errors() {
+        assert_eq!(
+            Gpx::parse("<kml></kml>"),
+            Err(GpxError::NotGpx { root: "kml".into() })
+        );
+        assert_eq!(
+            Gpx::parse(r#"<gpx><wpt lat="1"/></gpx>"#),
+            Err(GpxError::MissingAttribute {
+                element: "wpt".into(),
+                attribute: "lon"
+            })
+        );
+        assert_eq!(
+            Gpx::parse(r#"<gpx><wpt lat="1" lon="2"><time>noon</time></wpt></gpx>"#),
+            Err(GpxError::InvalidTime {
+                value: "noon".into()
+            })
+        );
+        assert!(matches!(
+            Gpx::parse("<gpx><trk></gpx>"),
+            Err(GpxError::Xml(XmlError::MismatchedTag { .. }))
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn xml_nesting_is_limited() {
+        let nested = |depth| format!("<gpx>{}{}</gpx>", "<a>".repeat(depth), "</a>".repeat(depth));
+        assert!(Gpx::parse(&nested(200)).is_ok());
+        assert!(matches!(
+            Gpx::parse(&nested(100_000)),
+            Err(GpxError::Xml(XmlError::TooDeep { .. }))
+        ));
+
+        let kml = format!(
+            "<kml>{}{}</kml>",
+            "<a>".repeat(100_000),
+            "</a>".repeat(100_000)
+        );
+        assert!(matches!(
+            Kml::parse(&kml),
+            Err(KmlError::Xml(XmlError::TooDeep { .. }))
+        ));
+    }
+
+    // ==================== KML Tests ====================
+
+    #[cfg(feature = "std")]
+    const SAMPLE_KML: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<kml xmlns="http://www.opengis.net/kml/2.2">
+  <Document>
+    <name>Territories</name>
+    <Folder>
+      <Placemark>
+        <name>Office</name>
+        <description>Head office</description>
+        <ExtendedData>
+          <Data name="region"><value>West</value></Data>
+        </ExtendedData>
+        <Point><coordinates>-111.7656248,40.7885447,0</coordinates></Point>
+      </Placemark>
+      <Placemark>
+        <name>Territory A</name>
+        <Polygon>
+          <outerBoundaryIs><LinearRing><coordinates>
+            0,0 10,0 10,10 0,10 0,0
+          </coordinates></LinearRing></outerBoundaryIs>
+          <innerBoundaryIs><LinearRing><coordinates>
+            2,2 3,2 3,3 2,2
+          </coordinates></LinearRing></innerBoundaryIs>
+        </Polygon>
+      </Placemark>
+    </Folder>
+    <Placemark>
+      <name>Route</name>
+      <LineString><coordinates>-111.76,40.78 -111.69,40.79</coordinates></LineString>
+    </Placemark>
+  </Document>
+</kml>"#;
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn kml_parse_placemarks() {
+        let kml: Kml = SAMPLE_KML.parse().unwrap();
+
+        assert_eq!(kml.name.as_deref(), Some("Territories"));
+        assert_eq!(kml.placemarks.len(), 3);
+
+        let office = &kml.placemarks[0];
+        assert_eq!(office.name.as_deref(), Some("Office"));
+        assert_eq!(office.description.as_deref(), Some("Head office"));
+        assert_eq!(office.property("region"), Some("West"));
+        assert_eq!(
+            office.geometry,
+            Some(Geometry::Point(Location::from(40.7885447, -111.7656248)))
+        );
+
+        match &kml.placemarks[1].geometry {
+            Some(Geometry::Polygon(polygon)) => {
+                assert_eq!(polygon.exterior.points.len(), 5);
+                assert_eq!(polygon.interiors.len(), 1);
+                assert_eq!(polygon.interiors[0].points[1], Location::from(2.0, 3.0));
+            }
+            other => panic!("expected polygon, got {:?}", other),
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn kml_round_trip() {
+        let kml = Kml::parse(SAMPLE_KML).unwrap();
+        let written = kml.to_kml_string();
+        assert_eq!(Kml::parse(&written).unwrap(), kml);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn kml_multi_geometry() {
+        let mut placemark = Placemark::from(Geometry::MultiLineString(vec![
+            LineString::from(vec![Location::from(1.0, 2.0), Location::from(3.0, 4.0)]),
+            LineString::from(vec![Location::from(5.0, 6.0), Location::from(7.0, 8.0)]),
+        ]));
+        placemark.name = Some("Roads & paths".into());
+        let kml = Kml {
+            name: None,
+            placemarks: vec![placemark],
+        };
+
+        let written = kml.to_kml_string();
+        assert!(written.contains("<name>Roads &amp; paths</name>"));
+        assert_eq!(Kml::parse(&written).unwrap(), kml);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn kml_errors() {
+        assert_eq!(
+            Kml::parse("<gpx/>"),
+            Err(KmlError::NotKml { root: "gpx".into() })
+        );
+        assert_eq!(
+            Kml::parse(
+                "<kml><Placemark><Point><coordinates>1;2</coordinates></Point></Placemark></kml>"
+            ),
+            Err(KmlError::InvalidCoordinates {
+                value: "1;2".into()
+            })
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn kml_mixed_multi_geometry_is_split() {
+        let kml = Kml::parse(
+            "<kml><Placemark><name>Site</name><MultiGeometry>\
+             <Point><coordinates>1,2</coordinates></Point>\
+             <Polygon><outerBoundaryIs><LinearRing><coordinates>0,0 1,0 1,1 0,0</coordinates>\
+             </LinearRing></outerBoundaryIs></Polygon></MultiGeometry></Placemark></kml>",
+        )
+        .unwrap();
+
+        assert_eq!(kml.placemarks.len(), 2);
+        assert!(kml
+            .placemarks
+            .iter()
+            .all(|placemark| placemark.name.as_deref() == Some("Site")));
+        assert_eq!(
+            kml.placemarks[0].geometry,
+            Some(Geometry::MultiPoint(vec![Location::from(2.0, 1.0)]))
+        );
+        assert!(matches!(
+            kml.placemarks[1].geometry,
+            Some(Geometry::MultiPolygon(ref polygons)) if polygons.len() == 1
+        ));
+    }
+
+    #[test]
+    #[cfg(all(feature = "serde", feature = "std"))]
+    fn kml_placemark_to_feature() {
+        let kml = Kml::parse(SAMPLE_KML).unwrap();
+        let feature: Feature = kml.placemarks[0].clone().into();
+
+        assert_eq!(feature.properties["name"], "Office");
+        assert_eq!(feature.properties["region"], "West");
+        let placemark: Placemark = feature.into();
+        assert_eq!(placemark, kml.placemarks[0]);
+    }
+
+    #[test]
+    #[cfg(feature = "kmz")]
+    fn kmz_round_trip() {
+        let kml = Kml::parse(SAMPLE_KML).unwrap();
+        let kmz = kml.to_kmz();
+
+        assert_eq!(&kmz[..2], b"PK");
+        assert_eq!(Kml::from_kmz(&kmz).unwrap(), kml);
+        assert!(matches!(
+            Kml::from_kmz(b"not a zip"),
+            Err(KmlError::Archive(_))
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "kmz")]
+    fn kmz_size_limit() {
+        let huge = Kml {
+            name: Some("x".repeat(2 << 20)),
+            placemarks: vec![],
+        };
+        assert!(matches!(
+            Kml::from_kmz(&huge.to_kmz()),
+            Err(KmlError::Archive(message)) if message.contains("larger than")
+        ));
+    }
+
+    // ==================== NMEA Tests ====================
+
+    #[test]
+    fn nmea_gga() {
+        let sentence =
+            parse_nmea("$GPGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*47\r\n")
+                .unwrap();
+
+        match sentence {
+            NmeaSentence::Gga(gga) => {
+                let location = gga.location.unwrap();
+                assert!((location.latitude - 48.1173).abs() < 0.000001);
+                assert!((location.longitude - 11.516666).abs() < 0.000001);
+                assert_eq!(gga.fix_quality, FixQuality::Gps);
+                assert_eq!(gga.satellites, Some(8));
+                assert_eq!(gga.hdop, Some(0.9));
+                assert_eq!(gga.altitude, Some(545.4));
+                assert_eq!(gga.geoid_separation, Some(46.9));
+                assert_eq!(
+                    gga.time,
+                    Some(NmeaTime {
+                        hour: 12,
+                        minute: 35,
+                        second: 19.
+                    })
+                );
+            }
+            other => panic!("expected GGA, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn nmea_rmc_with_timestamp() {
+        let sentence =
+            parse_nmea("$GPRMC,123519,A,4807.038,N,01131.000,E,022.4,084.4,230394,003.1,W*6A")
+                .unwrap();
+
+        match sentence {
+            NmeaSentence::Rmc(rmc) => {
+                assert!(rmc.valid);
+                assert_eq!(rmc.speed_knots, Some(22.4));
+                assert_eq!(rmc.course, Some(84.4));
+                assert_eq!(rmc.magnetic_variation, Some(-3.1));
+                assert_eq!(
+                    rmc.timestamp(),
+                    Some(Timestamp::from_utc(1994, 3, 23, 12, 35, 19.))
+                );
+            }
+            other => panic!("expected RMC, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn nmea_gll_southern_western_hemisphere() {
+        let sentence = parse_nmea("$GPGLL,4916.45,N,12311.12,W,225444,A*31").unwrap();
+        let location = sentence.location().unwrap();
+
+        assert!((location.latitude - 49.274166).abs() < 0.000001);
+        assert!((location.longitude - -123.185333).abs() < 0.000001);
+    }
+
+    #[test]
+    fn nmea_vtg_and_gsa() {
+        let vtg = parse_nmea("$GPVTG,054.7,T,034.4,M,005.5,N,010.2,K*48").unwrap();
+        assert_eq!(
+            vtg,
+            NmeaSentence::Vtg(Vtg {
+                course_true: Some(54.7),
+                course_magnetic: Some(34.4),
+                speed_knots: Some(5.5),
+                speed_kmh: Some(10.2),
+            })
+        );
+
+        match parse_nmea("$GPGSA,A,3,04,05,,09,12,,,24,,,,,2.5,1.3,2.1*39").unwrap() {
+            NmeaSentence::Gsa(gsa) => {
+                assert!(gsa.automatic);
+                assert_eq!(gsa.fix_type, FixType::Fix3D);
+                assert_eq!(gsa.satellites[..4], [Some(4), Some(5), None, Some(9)]);
+                assert_eq!(gsa.pdop, Some(2.5));
+                assert_eq!(gsa.vdop, Some(2.1));
+            }
+            other => panic!("expected GSA, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn nmea_no_fix_has_no_location() {
+        let sentence = parse_nmea("$GNGGA,,,,,,0,00,99.99,,,,,,*56").unwrap();
+        assert!(sentence.location().is_none());
+    }
+
+    #[test]
+    fn nmea_errors() {
+        assert_eq!(
+            parse_nmea("GPGLL,4916.45,N,12311.12,W,225444,A*31"),
+            Err(NmeaError::MissingStart)
+        );
+        assert_eq!(
+            parse_nmea("$GPGLL,4916.45,N,12311.12,W,225444,A"),
+            Err(NmeaError::MissingChecksum)
+        );
+        assert_eq!(
+            parse_nmea("$GPGLL,4916.46,N,12311.12,W,225444,A*31"),
+            Err(NmeaError::ChecksumMismatch {
+                expected: 0x31,
+                computed: 0x32
+            })
+        );
+        assert_eq!(
+            parse_nmea("$GPGSV,1,1,00*79"),
+            Err(NmeaError::UnsupportedSentence { kind: *b"GSV" })
+        );
+        assert_eq!(
+            parse_nmea("$GPGLL,4916.45,X,12311.12,W,225444,A*27"),
+            Err(NmeaError::InvalidField { field: 2 })
+        );
+        assert_eq!(
+            parse_nmea("$GPGLL,4916.45,N,12311.12,W,225444,A*+1"),
+            Err(NmeaError::MissingChecksum)
+        );
+    }
+
+    #[test]
+    fn nmea_rejects_impossible_values() {
+        for (sentence, field) in [
+            ("$GPGLL,9016.45,N,12311.12,W,225444,A*35", 1),
+            ("$GPGLL,4916.45,N,18111.12,W,225444,A*39", 3),
+            ("$GPGLL,inf,N,12311.12,W,225444,A*75", 1),
+            (
+                "$GPRMC,123519,A,4807.038,N,01131.000,E,022.4,084.4,310224,003.1,W*63",
+                9,
+            ),
+        ] {
+            assert_eq!(
+                parse_nmea(sentence),
+                Err(NmeaError::InvalidField { field }),
+                "{}",
+                sentence
+            );
+        }
+        // 2024 is a leap year
+        assert!(
+            parse_nmea("$GPRMC,123519,A,4807.038,N,01131.000,E,022.4,084.4,290224,003.1,W*6A")
+                .is_ok()
+        );
+    }
+
+    // ==================== DMS Tests ====================
+
+    #[test]
+    fn dms_parse_formats() {
+        let expected = 40.788_544_7;
+        for input in [
+            "40.7885447",
+            "40°47'18.761\"N",
+            "40° 47' 18.761\" N",
+            "N 40 47 18.761",
+            "40:47:18.761",
+            "40°47.31268'",
+        ] {
+            let value = parse_dms(input).unwrap();
+            assert!((value - expected).abs() < 1e-6, "{} -> {}", input, value);
+        }
+        assert!((parse_dms("111°45'56.2\"W").unwrap() + 111.765_611).abs() < 1e-5);
+        assert!((parse_dms("-33.5").unwrap() + 33.5).abs() < 1e-12);
+        assert!((parse_dms("33 30 S").unwrap() + 33.5).abs() < 1e-12);
+    }
+
+    #[test]
+    fn dms_parse_errors() {
+        assert_eq!(parse_dms(""), Err(DmsError::Empty));
+        assert_eq!(parse_dms("40 61 0"), Err(DmsError::OutOfRange));
+        assert_eq!(parse_dms("-40 N"), Err(DmsError::ConflictingSign));
+        assert_eq!(
+            parse_dms("1 2 3 4"),
+            Err(DmsError::InvalidNumber { position: 6 })
+        );
+        assert_eq!(
+            parse_dms("40x"),
+            Err(DmsError::UnexpectedCharacter {
+                character: 'x',
+                position: 2
+            })
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn dms_display_round_trip() {
+        let dms = Dms::from_decimal(-111.765_624_8, Axis::Longitude);
+        assert_eq!(dms.degrees, 111);
+        assert_eq!(dms.minutes, 45);
+        assert_eq!(dms.hemisphere, 'W');
+        assert!((dms.to_decimal() + 111.765_624_8).abs() < 1e-12);
+
+        let text = format!("{}", dms);
+        assert_eq!(text, "111°45'56.249\"W");
+        assert!((parse_dms(&text).unwrap() + 111.765_624_8).abs() < 1e-6);
+        assert_eq!(
+            format!("{:.0}", Dms::from_decimal(10.999_999, Axis::Latitude)),
+            "11°0'0\"N"
+        );
+    }
+
+    // ==================== CSV Tests ====================
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn csv_read_by_name_with_extra_fields() {
+        let input = "name,Latitude,note,Longitude\n\
+                     Home,40.7885,\"quoted, with comma\",-111.7656\n\
+                     \n\
+                     Work,\"40.5\",\"multi\nline \"\"note\"\"\",-111.5\n";
+        let mut reader = CsvReader::new(input.as_bytes(), CsvOptions::default());
+        let records: Vec<CsvRecord> = reader.by_ref().map(Result::unwrap).collect();
+
+        assert_eq!(reader.extra_headers(), vec!["name", "note"]);
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].line, 2);
+        assert_eq!(records[0].location, Location::from(40.7885, -111.7656));
+        assert_eq!(records[0].extra, vec!["Home", "quoted, with comma"]);
+        assert_eq!(records[1].line, 4);
+        assert_eq!(records[1].extra, vec!["Work", "multi\nline \"note\""]);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn csv_read_by_index_with_dms() {
+        let options = CsvOptions {
+            latitude: CsvColumn::Index(1),
+            longitude: CsvColumn::Index(0),
+            delimiter: ';',
+            has_header: false,
+            format: CoordinateFormat::Dms,
+            ..CsvOptions::default()
+        };
+        let input = "111°45'56.2\"W;40°47'18.8\"N;peak\n";
+        let record = CsvReader::new(input.as_bytes(), options)
+            .next()
+            .unwrap()
+            .unwrap();
+        assert!((record.location.latitude - 40.788_556).abs() < 1e-5);
+        assert!((record.location.longitude + 111.765_611).abs() < 1e-5);
+        assert_eq!(record.extra, vec!["peak"]);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn csv_invalid_rows() {
+        let input = "latitude,longitude\n1,2\nabc,3\n4\n5,6\n";
+
+        let results: Vec<_> = CsvReader::new(input.as_bytes(), CsvOptions::default()).collect();
+        assert_eq!(results.len(), 4);
+        assert!(matches!(
+            results[1],
+            Err(CsvError::InvalidCoordinate {
+                line: 3,
+                column: 0,
+                ..
+            })
+        ));
+        assert!(matches!(
+            results[2],
+            Err(CsvError::MissingField { line: 4, column: 1 })
+        ));
+
+        let options = CsvOptions {
+            invalid_rows: InvalidRows::Skip,
+            ..CsvOptions::default()
+        };
+        let mut reader = CsvReader::new(input.as_bytes(), options);
+        let locations: Vec<Location> = reader.by_ref().map(|r| r.unwrap().location).collect();
+        assert_eq!(
+            locations,
+            vec![Location::from(1., 2.), Location::from(5., 6.)]
+        );
+        assert_eq!(reader.skipped_rows(), 2);
+
+        let mut missing = CsvReader::new("lat,lng\n1,2\n3,4\n".as_bytes(), CsvOptions::default());
+        assert!(matches!(
+            missing.next(),
+            Some(Err(CsvError::MissingColumn { .. }))
+        ));
+        assert!(missing.next().is_none());
+        let unterminated = CsvReader::new(
+            "latitude,longitude\n1,\"2\n".as_bytes(),
+            CsvOptions::default(),
+        )
+        .next();
+        assert!(matches!(
+            unterminated,
+            Some(Err(CsvError::UnterminatedQuote { line: 2 }))
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn csv_write_and_read_back() {
+        let mut writer = CsvWriter::new(Vec::new(), ',', CoordinateFormat::Decimal);
+        writer
+            .write_header("latitude", "longitude", &["name"])
+            .unwrap();
+        writer
+            .write_record(
+                &Location::from(40.7885447, -111.7656248),
+                &["Salt \"Lake\", UT"],
+            )
+            .unwrap();
+        let output = String::from_utf8(writer.into_inner()).unwrap();
+        assert_eq!(
+            output,
+            "latitude,longitude,name\n40.7885447,-111.7656248,\"Salt \"\"Lake\"\", UT\"\n"
+        );
+
+        let record = CsvReader::new(output.as_bytes(), CsvOptions::default())
+            .next()
+            .unwrap()
+            .unwrap();
+        assert_eq!(record.location, Location::from(40.7885447, -111.7656248));
+        assert_eq!(record.extra, vec!["Salt \"Lake\", UT"]);
+
+        let mut writer = CsvWriter::new(Vec::new(), '\t', CoordinateFormat::Dms);
+        writer
+            .write_record::<&str>(&Location::from(-33.5, 151.25), &[])
+            .unwrap();
+        let output = String::from_utf8(writer.into_inner()).unwrap();
+        assert_eq!(output, "\"33°30'0.000\"\"S\"\t\"151°15'0.000\"\"E\"\n");
+
+        let options = CsvOptions {
+            latitude: CsvColumn::Index(0),
+            longitude: CsvColumn::Index(1),
+            delimiter: '\t',
+            has_header: false,
+            format: CoordinateFormat::Dms,
+            ..CsvOptions::default()
+        };
+        let record = CsvReader::new(output.as_bytes(), options)
+            .next()
+            .unwrap()
+            .unwrap();
+        assert_eq!(record.location, Location::from(-33.5, 151.25));
+    }
+
+    // ==================== Ball Tree Tests ====================
+
+    /// Deterministic pseudo random locations spread over the whole globe.
+    #[cfg(feature = "std")]
+    fn scattered_locations(count: usize) -> Vec<Location> {
+        let mut state = 0x2545_f491_4f6c_dd1du64;
+        let mut next = move || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            (state >> 11) as f64 / (1u64 << 53) as f64
+        };
+        (0..count)
+            .map(|_| {
+                let latitude = (2. * next() - 1.).asin().to_degrees();
+                Location::from(latitude, next() * 360. - 180.)
+            })
+            .collect()
+    }
+
+    #[cfg(feature = "std")]
+    fn great_circle_km(a: &Location, b: &Location) -> f64 {
+        let (lat1, lat2) = (a.latitude.to_radians(), b.latitude.to_radians());
+        let d_lat = lat2 - lat1;
+        let d_lng = (b.longitude - a.longitude).to_radians();
+        let h = (d_lat / 2.).sin().powi(2) + lat1.cos() * lat2.cos() * (d_lng / 2.).sin().powi(2);
+        2. * h.sqrt().asin() * 6378.137
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn ball_tree_matches_brute_force() {
+        let locations = scattered_locations(500);
+        let tree: BallTree<usize> = locations.iter().cloned().zip(0..).collect();
+        assert_eq!(tree.len(), 500);
+
+        for query in scattered_locations(520).iter().skip(500) {
+            let mut expected: Vec<(usize, f64)> = locations
+                .iter()
+                .enumerate()
+                .map(|(i, l)| (i, great_circle_km(query, l)))
+                .collect();
+            expected.sort_by(|a, b| a.1.total_cmp(&b.1));
+
+            let found = tree.k_nearest(query, 5);
+            assert_eq!(found.len(), 5);
+            for ((item, distance), (index, km)) in found.iter().zip(&expected) {
+                assert_eq!(**item, *index);
+                assert!((distance.kilometers() - km).abs() < 1e-6);
+            }
+
+            let radius = Distance::from_kilometers(2000.);
+            let within: Vec<usize> = tree
+                .within(query, &radius)
+                .iter()
+                .map(|(i, _)| **i)
+                .collect();
+            let brute: Vec<usize> = expected
+                .iter()
+                .filter(|(_, km)| *km <= 2000.)
+                .map(|(i, _)| *i)
+                .collect();
+            assert_eq!(within, brute);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn ball_tree_antimeridian_and_poles() {
+        let tree = BallTree::bulk_load(vec![
+            (Location::from(0., 179.9), "east of fiji"),
+            (Location::from(0., -170.), "samoa-ish"),
+            (Location::from(0., 175.), "west"),
+            (Location::from(89.9, 0.), "north pole a"),
+            (Location::from(89.9, 180.), "north pole b"),
+        ]);
+
+        let (item, distance) = tree.nearest(&Location::from(0., -179.9)).unwrap();
+        assert_eq!(*item, "east of fiji");
+        assert!((distance.kilometers() - 22.26).abs() < 0.01);
+
+        let near_pole = tree.within(&Location::from(89.9, 90.), &Distance::from_kilometers(20.));
+        assert_eq!(near_pole.len(), 2);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn ball_tree_insert_and_remove() {
+        let mut tree = BallTree::new();
+        assert!(tree.is_empty());
+        assert!(tree.nearest(&Location::from(0., 0.)).is_none());
+
+        let locations = scattered_locations(100);
+        for (i, location) in locations.iter().enumerate() {
+            tree.insert(location.clone(), i);
+        }
+        assert_eq!(tree.len(), 100);
+        assert_eq!(tree.iter().count(), 100);
+
+        let target = &locations[42];
+        assert_eq!(*tree.nearest(target).unwrap().0, 42);
+        assert_eq!(tree.remove(target, &42), Some(42));
+        assert_eq!(tree.remove(target, &42), None);
+        assert_eq!(tree.len(), 99);
+        assert_ne!(*tree.nearest(target).unwrap().0, 42);
+        assert!(tree.k_nearest(target, 0).is_empty());
+        assert_eq!(tree.k_nearest(target, 500).len(), 99);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn ball_tree_sequential_inserts_stay_balanced() {
+        let mut tree = BallTree::new();
+        for i in 0..20_000 {
+            tree.insert(Location::from(0., i as f64 * 0.001), i);
+        }
+        assert_eq!(tree.len(), 20_000);
+        assert_eq!(
+            *tree.nearest(&Location::from(0., 12.3454)).unwrap().0,
+            12_345
+        );
+        assert_eq!(
+            tree.k_nearest(&Location::from(0., 0.), usize::MAX).len(),
+            20_000
+        );
+    }
+
+    // ==================== Bounding Box & R-tree Tests ====================
+
+    #[test]
+    fn bounding_box_antimeridian() {
+        let pacific = BoundingBox::new(-20., 170., 20., -170.);
+        assert!(pacific.crosses_antimeridian());
+        assert!(pacific.contains(&Location::from(0., 179.)));
+        assert!(pacific.contains(&Location::from(0., -175.)));
+        assert!(!pacific.contains(&Location::from(0., 0.)));
+        assert!(!pacific.contains(&Location::from(30., 179.)));
+
+        let (west, east) = pacific.split_antimeridian();
+        assert_eq!(west, BoundingBox::new(-20., 170., 20., 180.));
+        assert_eq!(east, Some(BoundingBox::new(-20., -180., 20., -170.)));
+
+        assert!(pacific.intersects(&BoundingBox::new(0., -172., 5., -160.)));
+        assert!(!pacific.intersects(&BoundingBox::new(0., -160., 5., 160.)));
+        assert!(pacific.intersects(&BoundingBox::new(0., 175., 5., -175.)));
+    }
+
+    #[cfg(feature = "std")]
+    fn sample_windows() -> Vec<BoundingBox> {
+        vec![
+            BoundingBox::new(-10., -20., 30., 40.),
+            BoundingBox::new(-60., 150., 60., -150.),
+            BoundingBox::new(70., -180., 90., 180.),
+            BoundingBox::new(-90., 179.5, 90., -179.5),
+            BoundingBox::new(0., 0., 0., 0.),
+        ]
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn rtree_points_match_brute_force() {
+        let locations = scattered_locations(1000);
+        let tree: RTree<usize> = locations.iter().cloned().zip(0..).collect();
+        let packed: PackedRTree<usize> = locations.iter().cloned().zip(0..).collect();
+        assert_eq!(tree.len(), 1000);
+        assert_eq!(packed.len(), 1000);
+
+        for window in sample_windows() {
+            let expected: Vec<usize> = (0..locations.len())
+                .filter(|&i| window.contains(&locations[i]))
+                .collect();
+            let mut found: Vec<usize> = tree.search(&window).into_iter().copied().collect();
+            found.sort();
+            assert_eq!(found, expected);
+            let mut found: Vec<usize> = packed.search(&window).into_iter().copied().collect();
+            found.sort();
+            assert_eq!(found, expected);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn rtree_boxes_crossing_antimeridian_found_once() {
+        let boxes = vec![
+            (BoundingBox::new(-5., 175., 5., -175.), "dateline"),
+            (BoundingBox::new(-5., -10., 5., 10.), "greenwich"),
+            (BoundingBox::new(-90., -180., 90., 180.), "world"),
+        ];
+        let tree: RTree<&str> = boxes.iter().copied().collect();
+        let packed = PackedRTree::new(boxes);
+
+        let check = |window: BoundingBox, expected: &[&str]| {
+            for found in [tree.search(&window), packed.search(&window)] {
+                let mut found: Vec<&str> = found.into_iter().copied().collect();
+                found.sort();
+                assert_eq!(found, expected, "{:?}", window);
+            }
+        };
+        check(
+            BoundingBox::new(-1., 170., 1., -170.),
+            &["dateline", "world"],
+        );
+        check(
+            BoundingBox::new(-1., 178., 1., 179.),
+            &["dateline", "world"],
+        );
+        check(
+            BoundingBox::new(-1., -179., 1., -178.),
+            &["dateline", "world"],
+        );
+        check(
+            BoundingBox::new(-1., -180., 1., 180.),
+            &["dateline", "greenwich", "world"],
+        );
+        check(BoundingBox::new(-1., 5., 1., 170.), &["greenwich", "world"]);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn rtree_remove() {
+        let locations = scattered_locations(200);
+        let mut tree: RTree<usize> = locations.iter().cloned().zip(0..).collect();
+        tree.insert(BoundingBox::new(-5., 175., 5., -175.), 1000);
+
+        let everything = BoundingBox::new(-90., -180., 90., 180.);
+        assert_eq!(tree.search(&everything).len(), 201);
+
+        for (i, location) in locations.iter().enumerate().step_by(2) {
+            let bbox = BoundingBox::from_location(location);
+            assert_eq!(tree.remove(&bbox, &i), Some(i));
+        }
+        let dateline = BoundingBox::new(-5., 175., 5., -175.);
+        assert_eq!(tree.remove(&dateline, &1000), Some(1000));
+        assert_eq!(tree.remove(&dateline, &1000), None);
+
+        assert_eq!(tree.len(), 100);
+        let mut found: Vec<usize> = tree.search(&everything).into_iter().copied().collect();
+        found.sort();
+        assert_eq!(found, (1..200).step_by(2).collect::<Vec<_>>());
+
+        for (i, location) in locations.iter().enumerate().skip(1).step_by(2) {
+            tree.remove(&BoundingBox::from_location(location), &i);
+        }
+        assert!(tree.is_empty());
+        assert!(tree.search(&everything).is_empty());
+        tree.insert_point(&Location::from(1., 1.), 7);
+        assert_eq!(tree.search(&everything), vec![&7]);
+    }
+
+    // ==================== Cell Tests ====================
+
+    #[test]
+    fn cell_hierarchy() {
+        let location = Location::from(40.7885447, -111.7656248);
+        let leaf = CellId::from_location(&location, MAX_CELL_LEVEL);
+        assert!(leaf.is_leaf());
+        assert_eq!(leaf.level(), 30);
+        assert!(leaf.children().is_none());
+
+        for level in 0..MAX_CELL_LEVEL {
+            let cell = CellId::from_location(&location, level);
+            assert_eq!(cell.level(), level);
+            assert_eq!(leaf.parent(level), cell);
+            assert!(cell.contains(&leaf));
+
+            let children = cell.children().unwrap();
+            let next = CellId::from_location(&location, level + 1);
+            assert!(children.contains(&next));
+            assert_eq!(children.iter().filter(|c| c.contains(&leaf)).count(), 1);
+            for child in children {
+                assert_eq!(child.parent(level), cell);
+                assert_eq!(child.level(), level + 1);
+            }
+        }
+        assert_eq!(
+            CellId::from_location(&location, 0),
+            CellId::from_face(leaf.face()).unwrap()
+        );
+        assert_eq!(CellId::from_raw(leaf.id()), Some(leaf));
+        assert_eq!(CellId::from_raw(0), None);
+        assert_eq!(CellId::from_raw(2), None);
+    }
+
+    #[test]
+    fn cell_centre_and_boundary() {
+        for location in [
+            Location::from(40.7885447, -111.7656248),
+            Location::from(-33.86, 151.21),
+            Location::from(89.99, 45.),
+            Location::from(0., 180.),
+        ] {
+            for level in [0, 5, 12, 20, 30] {
+                let cell = CellId::from_location(&location, level);
+                assert_eq!(CellId::from_location(&cell.centre(), level), cell);
+                // Corners are shared with the neighbouring cells, so nudge them toward the centre
+                let centre = cell.centre();
+                for corner in cell.boundary() {
+                    let inside = Location::from(
+                        corner.latitude + (centre.latitude - corner.latitude) * 0.01,
+                        corner.longitude + (centre.longitude - corner.longitude) * 0.01,
+                    );
+                    if (centre.longitude - corner.longitude).abs() < 90. {
+                        assert_eq!(CellId::from_location(&inside, level), cell);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn cell_neighbours() {
+        for location in [
+            Location::from(40.7885447, -111.7656248),
+            Location::from(45., 45.),
+            Location::from(-89.9, 10.),
+            Location::from(0., 179.999),
+        ] {
+            for level in [0, 3, 10, 25] {
+                let cell = CellId::from_location(&location, level);
+                let neighbours = cell.edge_neighbours();
+                for (k, neighbour) in neighbours.iter().enumerate() {
+                    assert_eq!(neighbour.level(), level);
+                    assert_ne!(*neighbour, cell);
+                    assert!(
+                        neighbour.edge_neighbours().contains(&cell),
+                        "{} is not a neighbour of {} ({})",
+                        k,
+                        level,
+                        location.latitude
+                    );
+                }
+            }
+        }
+        let face = CellId::from_face(0).unwrap();
+        let mut faces = face.edge_neighbours().map(|cell| cell.face());
+        faces.sort();
+        assert_eq!(faces, [1, 2, 4, 5]);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn cell_tokens() {
+        let cell = CellId::from_location(&Location::from(40.7885447, -111.7656248), 10);
+        let token = cell.to_string();
+        assert!(token.len() < 16);
+        assert_eq!(CellId::from_token(&token), Some(cell));
+        assert_eq!(CellId::from_face(0).unwrap().to_string(), "1");
+        assert_eq!(CellId::from_face(6), None);
+        assert_eq!(CellId::from_token("zz"), None);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn cell_cover_circle() {
+        let circle = Circle::from(Location::from(0., 180.), Distance::from_kilometers(300.));
+        let cells = CellId::cover_circle(&circle, 8, 1000).unwrap();
+        assert!(!cells.is_empty());
+
+        // Every location in the circle is in a covering cell
+        for location in scattered_locations(2000)
+            .iter()
+            .map(|l| Location::from(l.latitude / 30., 180. + l.longitude / 30.))
+            .map(|l| Location::from_normalized(l.latitude, l.longitude))
+            .filter(|l| circle.contains(l))
+        {
+            assert!(cells.contains(&CellId::from_location(&location, 8)));
+        }
+        // And every covering cell is close to the circle
+        for cell in &cells {
+            assert!(cell.centre().distance(&circle.centre).kilometers() < 360.);
+            assert_eq!(cell.level(), 8);
+        }
+        // The pole is the centre of a cube face, so it's a corner of four cells
+        let pole = Circle::from(Location::from(90., 0.), Distance::from_meters(1.));
+        assert_eq!(CellId::cover_circle(&pole, 3, 10).unwrap().len(), 4);
+        assert_eq!(CellId::cover_circle(&pole, 0, 10).unwrap().len(), 1);
+        assert_eq!(CellId::cover_circle(&pole, 3, 3), None);
+
+        // A fine level over a large area gives up instead of allocating millions of cells
+        let continent = Circle::from(Location::from(0., 0.), Distance::from_kilometers(3000.));
+        assert_eq!(CellId::cover_circle(&continent, 20, 10_000), None);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn cell_cover_polygon() {
+        let ring = vec![
+            Location::from(10., 170.),
+            Location::from(10., -170.),
+            Location::from(-10., -170.),
+            Location::from(-10., 170.),
+        ];
+        let cells = CellId::cover_polygon(&ring, 6, 10_000).unwrap();
+        for location in [
+            Location::from(0., 180.),
+            Location::from(9., 171.),
+            Location::from(-9., -171.),
+        ] {
+            assert!(cells.contains(&CellId::from_location(&location, 6)));
+        }
+        assert!(!cells.contains(&CellId::from_location(&Location::from(0., 0.), 6)));
+        assert!(!cells.contains(&CellId::from_location(&Location::from(30., 180.), 6)));
+
+        let reversed: Vec<Location> = ring.iter().rev().cloned().collect();
+        assert_eq!(CellId::cover_polygon(&reversed, 6, 10_000), Some(cells));
+        assert_eq!(CellId::cover_polygon(&ring[..2], 6, 1000), Some(vec![]));
+        assert_eq!(CellId::cover_polygon(&ring, 12, 10_000), None);
+    }
+
+    // ==================== Clustering Tests ====================
+
+    #[cfg(feature = "std")]
+    fn blob(centre: &Location, count: usize, spread: f64) -> Vec<Location> {
+        (0..count)
+            .map(|i| {
+                let angle = i as f64 * 2.399_963;
+                let r = spread * ((i + 1) as f64 / count as f64).sqrt();
+                Location::from_normalized(
+                    centre.latitude + r * angle.sin(),
+                    centre.longitude + r * angle.cos(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn dbscan_finds_clusters_and_noise() {
+        let mut locations = blob(&Location::from(40.76, -111.89), 30, 0.05);
+        locations.extend(blob(&Location::from(0., 179.99), 20, 0.05));
+        locations.push(Location::from(10., 10.));
+        locations.push(Location::from(-45., 100.));
+
+        let result = dbscan(&locations, &Distance::from_kilometers(5.), 4);
+        assert_eq!(result.clusters.len(), 2);
+        assert_eq!(result.noise, vec![50, 51]);
+        assert_eq!(result.clusters[0].members, (0..30).collect::<Vec<_>>());
+        assert_eq!(result.clusters[1].members, (30..50).collect::<Vec<_>>());
+
+        let salt_lake = &result.clusters[0].centre;
+        assert!(
+            salt_lake
+                .distance(&Location::from(40.76, -111.89))
+                .kilometers()
+                < 2.
+        );
+        // Averaged on the sphere, so the antimeridian cluster isn't centred near Greenwich
+        let dateline = &result.clusters[1].centre;
+        assert!(dateline.longitude.abs() > 179.);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn dbscan_edge_cases() {
+        assert_eq!(
+            dbscan(&[], &Distance::from_kilometers(1.), 2),
+            Clustering::default()
+        );
+
+        let single = [Location::from(1., 1.)];
+        let result = dbscan(&single, &Distance::from_kilometers(1.), 1);
+        assert_eq!(result.clusters.len(), 1);
+        assert_eq!(result.clusters[0].members, vec![0]);
+
+        let result = dbscan(&single, &Distance::from_kilometers(1.), 2);
+        assert!(result.clusters.is_empty());
+        assert_eq!(result.noise, vec![0]);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn grid_clusters_depend_on_zoom() {
+        let mut locations = blob(&Location::from(40.76, -111.89), 25, 0.2);
+        locations.extend(blob(&Location::from(40.23, -111.66), 25, 0.2));
+
+        let world = grid_clusters(&locations, 0, 256.);
+        assert_eq!(world.len(), 1);
+        assert_eq!(world[0].members.len(), 50);
+
+        let zoomed = grid_clusters(&locations, 12, 60.);
+        assert!(zoomed.len() > 10);
+        let total: usize = zoomed.iter().map(|c| c.members.len()).sum();
+        assert_eq!(total, 50);
+        for cluster in &zoomed {
+            for &member in &cluster.members {
+                assert!(cluster.centre.distance(&locations[member]).kilometers() < 5.);
+            }
+        }
+        assert!(grid_clusters(&[], 5, 60.).is_empty());
+    }
+
+    // ==================== Convex Hull Tests ====================
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn planar_hull() {
+        let locations = vec![
+            Location::from(0., 0.),
+            Location::from(0., 2.),
+            Location::from(2., 2.),
+            Location::from(2., 0.),
+            Location::from(1., 1.),
+            Location::from(0., 1.),
+            Location::from(2., 2.),
+        ];
+        assert_eq!(
+            planar_convex_hull(&locations),
+            vec![
+                Location::from(0., 0.),
+                Location::from(0., 2.),
+                Location::from(2., 2.),
+                Location::from(2., 0.),
+            ]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn hull_degenerate_inputs() {
+        assert!(planar_convex_hull(&[]).is_empty());
+        assert_eq!(spherical_convex_hull(&[]), Ok(vec![]));
+
+        let point = Location::from(5., 5.);
+        assert_eq!(
+            planar_convex_hull(&[point.clone(), point.clone()]),
+            vec![point.clone()]
+        );
+        assert_eq!(
+            spherical_convex_hull(&[point.clone(), point.clone()]),
+            Ok(vec![point])
+        );
+
+        let line = vec![
+            Location::from(0., 3.),
+            Location::from(0., 1.),
+            Location::from(0., 2.),
+            Location::from(0., 1.),
+        ];
+        assert_eq!(
+            planar_convex_hull(&line),
+            vec![Location::from(0., 1.), Location::from(0., 3.)]
+        );
+        // Points along the equator are on one great circle
+        assert_eq!(spherical_convex_hull(&line).unwrap().len(), 2);
+
+        let spread = [
+            Location::from(0., 0.),
+            Location::from(0., 120.),
+            Location::from(0., -120.),
+        ];
+        assert_eq!(
+            spherical_convex_hull(&spread),
+            Err(HullError::NotInHemisphere)
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn spherical_hull_across_antimeridian_and_pole() {
+        let locations = vec![
+            Location::from(10., 170.),
+            Location::from(10., -170.),
+            Location::from(-10., -170.),
+            Location::from(-10., 170.),
+            Location::from(0., 180.),
+            Location::from(5., 175.),
+        ];
+        let hull = spherical_convex_hull(&locations).unwrap();
+        assert_eq!(hull.len(), 4);
+        for corner in &locations[..4] {
+            assert!(hull.contains(corner));
+        }
+
+        let polar: Vec<Location> = (0..8)
+            .map(|i| Location::from(80., i as f64 * 45. - 180.))
+            .chain([Location::from(90., 0.), Location::from(85., 30.)])
+            .collect();
+        let hull = spherical_convex_hull(&polar).unwrap();
+        assert_eq!(hull.len(), 8);
+        assert!(hull.iter().all(|l| l.latitude == 80.));
+        // Counter-clockwise seen from above the north pole means longitude increases
+        for (i, corner) in hull.iter().enumerate() {
+            let next = &hull[(i + 1) % hull.len()];
+            assert_eq!((next.longitude - corner.longitude).rem_euclid(360.), 45.);
+        }
+    }
+
+    // ==================== Buffer Tests ====================
+
+    #[cfg(feature = "std")]
+    fn polygons(geometry: &Geometry) -> Vec<Polygon> {
+        match geometry {
+            Geometry::Polygon(polygon) => vec![polygon.clone()],
+            Geometry::MultiPolygon(polygons) => polygons.clone(),
+            _ => panic!("expected polygons, got {}", geometry.type_name()),
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn circle_ring() {
+        let circle = Circle::from(
+            Location::from(40.7885447, -111.7656248),
+            Distance::from_kilometers(10.),
+        );
+        let ring = circle.to_ring(64);
+        assert_eq!(ring.len(), 64);
+        for vertex in &ring {
+            assert!((great_circle_km(&circle.centre, vertex) - 10.).abs() < 1e-6);
+        }
+        // Counter-clockwise: the first vertex is due north, the next one is to its west
+        assert!(ring[0].latitude > circle.centre.latitude);
+        assert!(ring[1].longitude < ring[0].longitude);
+
+        let geometry = circle.to_geometry(64);
+        assert_eq!(polygons(&geometry)[0].exterior.points.len(), 65);
+        assert!(polygons(&geometry)[0].exterior.is_closed());
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn circle_geometry_antimeridian_and_pole() {
+        let dateline = Circle::from(Location::from(0., 179.9), Distance::from_kilometers(100.));
+        let parts = polygons(&dateline.to_geometry(32));
+        assert_eq!(parts.len(), 2);
+        for part in &parts {
+            let points = &part.exterior.points;
+            assert!(points.iter().all(|p| p.longitude.abs() <= 180.));
+            let (west, east) = (
+                points.iter().all(|p| p.longitude <= -179.),
+                points.iter().all(|p| p.longitude >= 178.),
+            );
+            assert!(west || east);
+        }
+
+        let arctic = Circle::from(Location::from(85., 30.), Distance::from_kilometers(1000.));
+        let parts = polygons(&arctic.to_geometry(72));
+        assert_eq!(parts.len(), 1);
+        let points = &parts[0].exterior.points;
+        assert!(points
+            .iter()
+            .any(|p| p.latitude == 90. && p.longitude == 180.));
+        assert!(points
+            .iter()
+            .any(|p| p.latitude == 90. && p.longitude == -180.));
+        assert!(points
+            .iter()
+            .all(|p| p.longitude.abs() <= 180. && p.latitude > 70.));
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn corridor_buffer() {
+        let route = LineString::from(vec![
+            Location::from(0., 0.),
+            Location::from(0., 1.),
+            Location::from(1., 1.),
+            Location::from(1., 1.),
+            Location::from(1., 2.),
+        ]);
+        let distance = Distance::from_kilometers(5.);
+        let ring = route.buffer(&distance, 8);
+        assert!(ring.len() > 20);
+        assert_ne!(ring.first(), ring.last());
+
+        // Every vertex is about 5km from the route, allowing for the mitred inner corners
+        let points: Vec<_> = route.points.iter().map(Vector3::from_location).collect();
+        for vertex in &ring {
+            let p = Vector3::from_location(vertex);
+            let closest = points
+                .windows(2)
+                .map(|w| angle_to_arc(&p, &w[0], &w[1]))
+                .fold(f64::INFINITY, f64::min);
+            let km = arc_length(closest).kilometers();
+            assert!((km - 5.).abs() < 0.01, "{} is {}km away", vertex, km);
+        }
+
+        // Points near the route are inside the ring, points far from it aren't
+        assert!(ring_contains(
+            &ring,
+            &Vector3::from_location(&Location::from(0.02, 0.5))
+        ));
+        assert!(ring_contains(
+            &ring,
+            &Vector3::from_location(&Location::from(0.5, 0.97))
+        ));
+        assert!(!ring_contains(
+            &ring,
+            &Vector3::from_location(&Location::from(0.5, 0.5))
+        ));
+
+        assert!(LineString::from(vec![]).buffer(&distance, 8).is_empty());
+        assert_eq!(
+            LineString::from(vec![Location::from(1., 1.)])
+                .buffer(&distance, 8)
+                .len(),
+            16
+        );
+        assert!(matches!(
+            LineString::from(vec![Location::from(0., 179.9), Location::from(0., -179.9)]).buffer_geometry(&distance, 8),
+            Geometry::MultiPolygon(parts) if parts.len() == 2
+        ));
+    }
+
+    // ==================== Enclosing Circle Tests ====================
+
+    /// The smallest circle through two or three of the points that contains all of them.
+    #[cfg(feature = "std")]
+    fn brute_force_enclosing_km(locations: &[Location]) -> f64 {
+        let n = locations.len();
+        let mut best = f64::INFINITY;
+        let mut candidates = Vec::new();
+        for i in 0..n {
+            for j in i + 1..n {
+                candidates.push(vec![&locations[i], &locations[j]]);
+                for k in j + 1..n {
+                    candidates.push(vec![&locations[i], &locations[j], &locations[k]]);
+                }
+            }
+        }
+        for points in candidates {
+            let vectors: Vec<Vector3> = points.iter().map(|l| Vector3::from_location(l)).collect();
+            let centre = if vectors.len() == 2 {
+                vectors[0].add(&vectors[1]).normalize().unwrap()
+            } else {
+                let normal = vectors[1]
+                    .sub(&vectors[0])
+                    .cross(&vectors[2].sub(&vectors[0]))
+                    .normalize()
+                    .unwrap();
+                if normal.dot(&vectors[0]) < 0. {
+                    normal.scale(-1.)
+                } else {
+                    normal
+                }
+            };
+            let radius = centre.angle(&vectors[0]);
+            if locations
+                .iter()
+                .all(|l| centre.angle(&Vector3::from_location(l)) <= radius + 1e-9)
+            {
+                best = best.min(arc_length(radius).kilometers());
+            }
+        }
+        best
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn minimum_enclosing_matches_brute_force() {
+        let all = scattered_locations(240);
+        for (n, chunk) in all.chunks(12).enumerate() {
+            // Squeeze the points into a region, sometimes around the antimeridian or a pole
+            let centre = [
+                Location::from(40., -111.),
+                Location::from(0., 180.),
+                Location::from(88., 0.),
+            ][n % 3]
+                .clone();
+            let locations: Vec<Location> = chunk
+                .iter()
+                .map(|l| {
+                    Location::from_normalized(
+                        centre.latitude + l.latitude / 20.,
+                        centre.longitude + l.longitude / 20.,
+                    )
+                })
+                .collect();
+
+            let circle = Circle::minimum_enclosing(&locations).unwrap();
+            for location in &locations {
+                assert!(
+                    great_circle_km(&circle.centre, location) <= circle.radius.kilometers() + 1e-6
+                );
+            }
+            let expected = brute_force_enclosing_km(&locations);
+            assert!(
+                (circle.radius.kilometers() - expected).abs() < 1e-6,
+                "{} vs {}",
+                circle.radius.kilometers(),
+                expected
+            );
+
+            let bounding = Circle::bounding(&locations).unwrap();
+            for location in &locations {
+                assert!(
+                    great_circle_km(&bounding.centre, location)
+                        <= bounding.radius.kilometers() + 1e-6
+                );
+            }
+            assert!(bounding.radius.kilometers() >= expected - 1e-6);
+            assert!(bounding.radius.kilometers() < expected * 1.25);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn enclosing_circle_edge_cases() {
+        assert!(Circle::minimum_enclosing(&[]).is_none());
+        assert!(Circle::bounding(&[]).is_none());
+
+        let point = Location::from(12., 34.);
+        let circle = Circle::minimum_enclosing(&[point.clone(), point.clone()]).unwrap();
+        assert!(great_circle_km(&circle.centre, &point) < 1e-9);
+        assert!(circle.radius.kilometers() < 1e-9);
+
+        // Beats the plain average, which is pulled toward the cluster of points
+        let locations = [
+            Location::from(0., 0.),
+            Location::from(0., 0.1),
+            Location::from(0., 0.2),
+            Location::from(0., 10.),
+        ];
+        let circle = Circle::minimum_enclosing(&locations).unwrap();
+        assert!((circle.centre.longitude - 5.).abs() < 1e-9);
+        let average = find_center_point(&locations);
+        let farthest = |centre: &Location| {
+            locations
+                .iter()
+                .map(|l| great_circle_km(centre, l))
+                .fold(0., f64::max)
+        };
+        assert!(farthest(&circle.centre) < farthest(&average));
+
+        // Points around the whole equator don't fit within a hemisphere
+        let around: Vec<Location> = (0..6)
+            .map(|i| Location::from(0., -180. + i as f64 * 60.))
+            .collect();
+        assert!(Circle::minimum_enclosing(&around).is_none());
+        let spread = [
+            Location::from(60., 0.),
+            Location::from(-60., 120.),
+            Location::from(0., -120.),
+            Location::from(-10., 60.),
+        ];
+        assert!(Circle::minimum_enclosing(&spread).is_none());
+    }
+
+    // ==================== Error Tests ====================
+
+    #[test]
+    fn location_new_validates() {
+        assert_eq!(
+            Location::new(40.5, -111.2),
+            Ok(Location::from(40.5, -111.2))
+        );
+        assert_eq!(Location::new(90., 180.), Ok(Location::from(90., 180.)));
+        assert_eq!(
+            Location::new(90.5, 0.),
+            Err(LocationError::LatitudeOutOfRange { latitude: 90.5 })
+        );
+        assert_eq!(
+            Location::new(0., -180.1),
+            Err(LocationError::LongitudeOutOfRange { longitude: -180.1 })
+        );
+        assert_eq!(Location::new(f64::NAN, 0.), Err(LocationError::NonFinite));
+        assert_eq!(
+            Location::new(0., f64::INFINITY),
+            Err(LocationError::NonFinite)
+        );
+        assert_eq!(
+            Location::try_from((-33.9, 151.2)),
+            Location::new(-33.9, 151.2)
+        );
+        // The unchecked constructor still accepts anything
+        assert_eq!(Location::from(100., 0.).latitude, 100.);
+    }
+
+    #[test]
+    fn distance_try_new_validates() {
+        assert_eq!(
+            Distance::try_new(5., DistanceUnit::Miles),
+            Ok(Distance::from_miles(5.))
+        );
+        assert!(Distance::try_new(0., DistanceUnit::Meters).is_ok());
+        assert_eq!(
+            Distance::try_new(-1., DistanceUnit::Meters),
+            Ok(Distance::from_meters(-1.))
+        );
+        assert_eq!(
+            Distance::try_new(f64::NAN, DistanceUnit::Meters),
+            Err(DistanceError::NonFinite)
+        );
+        assert_eq!(
+            Distance::try_new(f64::NEG_INFINITY, DistanceUnit::Meters),
+            Err(DistanceError::NonFinite)
+        );
+    }
+
+    #[test]
+    fn crate_error_wraps_module_errors() {
+        fn checked(latitude: f64, radius: f64) -> Result<Circle, Error> {
+            let centre = Location::new(latitude, 0.)?;
+            let radius = Distance::try_new(radius, DistanceUnit::Meters)?;
+            Ok(Circle::from(centre, radius))
+        }
+        assert!(checked(10., 5.).is_ok());
+        assert!(matches!(
+            checked(95., 5.),
+            Err(Error::Location(LocationError::LatitudeOutOfRange { .. }))
+        ));
+        let error = checked(10., f64::NAN).unwrap_err();
+        assert!(matches!(error, Error::Distance(DistanceError::NonFinite)));
+        assert!(matches!(
+            Error::from(parse_dms("").unwrap_err()),
+            Error::Dms(DmsError::Empty)
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn crate_error_is_transparent() {
+        use core::error::Error as _;
+
+        // Reporters print the message and then each source, so the wrapped error isn't a source
+        let error: Error = Location::new(0., 200.).unwrap_err().into();
+        assert_eq!(error.to_string(), "longitude 200 is outside -180 to 180");
+        assert!(error.source().is_none());
+        let error: Error = decode_polyline("_", 5).unwrap_err().into();
+        assert!(matches!(error, Error::Polyline(_)));
+        assert_eq!(
+            error.to_string(),
+            decode_polyline("_", 5).unwrap_err().to_string()
+        );
+    }
+
+    // ==================== Distance Unit Tests ====================
+
+    #[test]
+    fn nautical_and_survey_units() {
+        let nautical = Distance::from(1., DistanceUnit::NauticalMiles);
+        assert_eq!(nautical, Distance::from_meters(1852.));
+        assert!((nautical.in_unit(DistanceUnit::Cables) - 10.).abs() < 1e-9);
+        assert!(
+            (Distance::from(1., DistanceUnit::Fathoms).in_unit(DistanceUnit::Feet) - 6.).abs()
+                < 1e-9
+        );
+
+        let furlong = Distance::from(1., DistanceUnit::Furlongs);
+        assert!((furlong.in_unit(DistanceUnit::Chains) - 10.).abs() < 1e-9);
+        assert!((furlong.in_unit(DistanceUnit::Yards) - 220.).abs() < 1e-9);
+
+        let survey_mile = Distance::from(1., DistanceUnit::UsSurveyMiles);
+        assert!((survey_mile.in_unit(DistanceUnit::UsSurveyFeet) - 5280.).abs() < 1e-9);
+        // The survey foot is two parts per million longer than the international foot
+        let difference = survey_mile.meters() - Distance::from_miles(1.).meters();
+        assert!((difference - 0.003219).abs() < 1e-6);
+    }
+
+    #[test]
+    fn astronomical_units() {
+        let au = Distance::from(1., DistanceUnit::AstronomicalUnits);
+        let light_seconds = au.in_unit(DistanceUnit::LightSeconds);
+        assert!((light_seconds - 499.004784).abs() < 1e-6);
+        assert_eq!(
+            Distance::from(1., DistanceUnit::LightSeconds).kilometers(),
+            299_792.458
+        );
+    }
+
+    #[test]
+    fn unit_names() {
+        assert_eq!(DistanceUnit::NauticalMiles.abbreviation(), "nmi");
+        assert_eq!(DistanceUnit::NauticalMiles.name(), "nautical miles");
+        assert_eq!(DistanceUnit::UsSurveyFeet.abbreviation(), "ftUS");
+        assert_eq!(DistanceUnit::AstronomicalUnits.name(), "astronomical units");
+        assert_eq!(DistanceUnit::Meters.name(), "meters");
+    }
+
+    // ==================== Distance Parsing Tests ====================
+
+    fn parsed(input: &str) -> Distance {
+        input.parse().unwrap()
+    }
+
+    #[test]
+    fn parse_simple_distances() {
+        assert_eq!(parsed("5km"), Distance::from_kilometers(5.));
+        assert_eq!(parsed("3.2 mi"), Distance::from_miles(3.2));
+        assert_eq!(
+            parsed("1,200 ft"),
+            Distance::from(1200., DistanceUnit::Feet)
+        );
+        assert_eq!(
+            parsed("12 nmi"),
+            Distance::from(12., DistanceUnit::NauticalMiles)
+        );
+        assert_eq!(parsed("  0.5 Meters "), Distance::from_meters(0.5));
+        assert_eq!(parsed("1 metre"), Distance::from_meters(1.));
+        assert_eq!(parsed(".25 mile"), Distance::from_miles(0.25));
+        assert_eq!(parsed("-40m"), Distance::from_meters(-40.));
+        assert_eq!(
+            parsed("3 light-seconds"),
+            Distance::from(3., DistanceUnit::LightSeconds)
+        );
+        assert_eq!(
+            parsed("1 US survey foot"),
+            Distance::from(1., DistanceUnit::UsSurveyFeet)
+        );
+    }
+
+    #[test]
+    fn parse_compound_distances() {
+        let distance = parsed("2 miles 300 yards");
+        assert!((distance.in_unit(DistanceUnit::Yards) - 3820.).abs() < 1e-9);
+        assert!((distance.miles() - 2.170_454_5).abs() < 1e-6);
+
+        let height = parsed("5'6\"");
+        assert!((height.in_unit(DistanceUnit::Inches) - 66.).abs() < 1e-9);
+        assert!((parsed("-1 km 500 m").meters() + 1500.).abs() < 1e-9);
+    }
+
+    #[test]
+    fn parse_distance_units() {
+        for unit in DistanceUnit::ALL {
+            assert_eq!(unit.abbreviation().parse(), Ok(unit));
+            assert_eq!(unit.name().parse(), Ok(unit));
+            assert_eq!(unit.singular_name().parse(), Ok(unit));
+        }
+        assert_eq!("NM".parse(), Ok(DistanceUnit::NauticalMiles));
+        assert_eq!("Inch".parse(), Ok(DistanceUnit::Inches));
+        assert_eq!(
+            "parsecs".parse::<DistanceUnit>(),
+            Err(ParseDistanceError::UnknownUnit { position: 0 })
+        );
+    }
+
+    #[test]
+    fn parse_distance_errors() {
+        assert_eq!("".parse::<Distance>(), Err(ParseDistanceError::Empty));
+        assert_eq!("   ".parse::<Distance>(), Err(ParseDistanceError::Empty));
+        assert_eq!(
+            "12".parse::<Distance>(),
+            Err(ParseDistanceError::MissingUnit { position: 2 })
+        );
+        assert_eq!(
+            "12 parsecs".parse::<Distance>(),
+            Err(ParseDistanceError::UnknownUnit { position: 3 })
+        );
+        assert_eq!(
+            "km".parse::<Distance>(),
+            Err(ParseDistanceError::InvalidNumber { position: 0 })
+        );
+        assert_eq!(
+            "1.2.3 m".parse::<Distance>(),
+            Err(ParseDistanceError::InvalidNumber { position: 0 })
+        );
+        assert_eq!(
+            "1,,200 m".parse::<Distance>(),
+            Err(ParseDistanceError::InvalidNumber { position: 0 })
+        );
+        assert_eq!(
+            "2 mi 3".parse::<Distance>(),
+            Err(ParseDistanceError::MissingUnit { position: 6 })
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn parse_distance_display_round_trip() {
+        for unit in DistanceUnit::ALL {
+            let distance = Distance::from(1234.5, unit);
+            let text = distance.to_string();
+            assert_eq!(parsed(&text), distance);
+            assert_eq!(parsed(&text).to_string(), text);
+        }
+        assert_eq!(
+            parsed(&Distance::from_meters(-2.5).to_string()),
+            Distance::from_meters(-2.5)
+        );
+    }
+
+    // ==================== Distance Formatting Tests ====================
+
+    /// Formats into a stack buffer, so these tests also run without std.
+    fn humanized(distance: Distance, format: DistanceFormat) -> ([u8; 64], usize) {
+        struct Out([u8; 64], usize);
+        impl core::fmt::Write for Out {
+            fn write_str(&mut self, s: &str) -> core::fmt::Result {
+                self.0[self.1..self.1 + s.len()].copy_from_slice(s.as_bytes());
+                self.1 += s.len();
+                Ok(())
+            }
+        }
+        let mut out = Out([0; 64], 0);
+        format.write(&distance, &mut out).unwrap();
+        (out.0, out.1)
+    }
+
+    fn assert_humanized(distance: Distance, format: DistanceFormat, expected: &str) {
+        let (bytes, length) = humanized(distance, format);
+        assert_eq!(core::str::from_utf8(&bytes[..length]).unwrap(), expected);
+    }
+
+    #[test]
+    fn humanize_picks_metric_units() {
+        let metric = DistanceFormat::default();
+        assert_humanized(Distance::from_meters(850.), metric, "850 m");
+        assert_humanized(Distance::from_meters(1234.), metric, "1.2 km");
+        assert_humanized(Distance::from_kilometers(0.04), metric, "40 m");
+        assert_humanized(Distance::from_meters(0.4), metric, "40 cm");
+        assert_humanized(Distance::from_meters(0.), metric, "0 m");
+        assert_humanized(Distance::from_miles(1.), metric, "1.6 km");
+        assert_humanized(Distance::from_meters(-850.), metric, "-850 m");
+        // Rounding carries up to the next unit
+        assert_humanized(Distance::from_meters(999.96), metric, "1 km");
+    }
+
+    #[test]
+    fn humanize_picks_imperial_and_nautical_units() {
+        let imperial = DistanceFormat::new(UnitSystem::Imperial);
+        assert_humanized(Distance::from(500., DistanceUnit::Feet), imperial, "500 ft");
+        assert_humanized(
+            Distance::from(2112., DistanceUnit::Feet),
+            imperial,
+            "0.4 mi",
+        );
+        assert_humanized(Distance::from_meters(0.1), imperial, "3.9 in");
+        assert_humanized(Distance::from_kilometers(10.), imperial, "6.2 mi");
+
+        let nautical = DistanceFormat::new(UnitSystem::Nautical);
+        assert_humanized(Distance::from_kilometers(25.), nautical, "13.5 nmi");
+        assert_humanized(Distance::from_meters(500.), nautical, "2.7 cb");
+        assert_humanized(Distance::from_meters(20.), nautical, "10.9 ftm");
+    }
+
+    #[test]
+    fn humanize_precision_and_names() {
+        let format = DistanceFormat {
+            system: UnitSystem::Imperial,
+            precision: 3,
+            full_names: true,
+        };
+        assert_humanized(Distance::from_miles(1.), format, "1 mile");
+        assert_humanized(Distance::from_miles(2.5), format, "2.5 miles");
+        assert_humanized(Distance::from_kilometers(10.), format, "6.214 miles");
+        assert_humanized(Distance::from(1., DistanceUnit::Feet), format, "1 foot");
+        assert_humanized(Distance::from(12., DistanceUnit::Feet), format, "12 feet");
+
+        let whole = DistanceFormat {
+            precision: 0,
+            ..DistanceFormat::default()
+        };
+        assert_humanized(Distance::from_meters(1499.), whole, "1 km");
+        assert_humanized(Distance::from_meters(12.4), whole, "12 m");
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn humanize_display() {
+        let distance = Distance::from_meters(1234.);
+        assert_eq!(
+            distance.humanize(DistanceFormat::default()).to_string(),
+            "1.2 km"
+        );
+        let full = DistanceFormat {
+            full_names: true,
+            ..DistanceFormat::new(UnitSystem::Nautical)
+        };
+        assert_eq!(
+            format!(
+                "{}",
+                Distance::from(1., DistanceUnit::NauticalMiles).humanize(full)
+            ),
+            "1 nautical mile"
+        );
+        // The output parses back into the same distance
+        let text = distance.humanize(DistanceFormat::default()).to_string();
+        assert_eq!(
+            text.parse::<Distance>().unwrap(),
+            Distance::from_kilometers(1.2)
+        );
+    }
+
+    // ==================== Distance Comparison Tests ====================
+
+    #[test]
+    fn approx_eq_with_tolerances() {
+        let a = Distance::from_kilometers(1.);
+        let b = Distance::from_meters(1000.4);
+        let half_meter = Tolerance::Absolute(Distance::from_meters(0.5));
+        assert!(a.approx_eq(&b, &half_meter));
+        assert!(b.approx_eq(&a, &half_meter));
+        assert!(!a.approx_eq(&b, &Tolerance::Absolute(Distance::from_meters(0.1))));
+
+        assert!(a.approx_eq(&b, &Tolerance::Relative(1e-3)));
+        assert!(!a.approx_eq(&b, &Tolerance::Relative(1e-4)));
+        let au = Distance::from(1., DistanceUnit::AstronomicalUnits);
+        let nudged = au + Distance::from_kilometers(1.);
+        assert!(au.approx_eq(&nudged, &Tolerance::Relative(1e-6)));
+
+        // The default equality truncates in the left operand's unit, so depends on order
+        assert!(a == b);
+        assert!(b != a);
+    }
+
+    #[test]
+    fn strict_eq_is_bitwise() {
+        let km = Distance::from_kilometers(1.);
+        assert!(km.strict_eq(&Distance::from_meters(1000.)));
+        assert!(!km.strict_eq(&Distance::from_meters(1000.0001)));
+        assert!(Distance::from_meters(-0.).strict_eq(&Distance::from_kilometers(0.)));
+        assert!(Distance::from_meters(f64::NAN).strict_eq(&Distance::from_miles(f64::NAN)));
+    }
+
+    #[test]
+    fn ordered_distance_sorts() {
+        let mut distances = [
+            OrderedDistance(Distance::from_miles(1.)),
+            OrderedDistance(Distance::from_meters(f64::NAN)),
+            OrderedDistance(Distance::from_kilometers(1.)),
+            OrderedDistance(Distance::from_meters(-5.)),
+            OrderedDistance(Distance::from(1., DistanceUnit::Yards)),
+        ];
+        distances.sort();
+        let meters = distances.each_ref().map(|d| d.0.meters());
+        assert_eq!(meters[..4], [-5., 0.9144, 1000., 1609.344]);
+        assert!(meters[4].is_nan());
+        assert_eq!(
+            distances.iter().max(),
+            Some(&OrderedDistance(Distance::from_meters(f64::NAN)))
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn ordered_distance_in_collections() {
+        use std::collections::{BinaryHeap, HashSet};
+
+        let mut heap: BinaryHeap<_> = [2., 7., 1.]
+            .into_iter()
+            .map(|km| OrderedDistance(Distance::from_kilometers(km)))
+            .collect();
+        heap.push(Distance::from_miles(3.).into());
+        assert_eq!(heap.pop().unwrap().0, Distance::from_kilometers(7.));
+        assert_eq!(heap.pop().unwrap().0, Distance::from_miles(3.));
+
+        let set: HashSet<OrderedDistance> = [
+            Distance::from_kilometers(1.),
+            Distance::from_meters(1000.),
+            Distance::from_meters(0.),
+            Distance::from_miles(-0.),
+            Distance::from_meters(1.),
+        ]
+        .into_iter()
+        .map(OrderedDistance)
+        .collect();
+        assert_eq!(set.len(), 3);
+    }
+
+    // ==================== Distance Arithmetic Tests ====================
+
+    #[test]
+    fn distance_operators() {
+        let km = Distance::from_kilometers(1.);
+        let mile = Distance::from_miles(1.);
+        assert_eq!(-km, Distance::from_kilometers(-1.));
+        assert!((mile / km - 1.609344).abs() < 1e-12);
+        assert_eq!(2. * km, km * 2.);
+        assert_eq!(
+            mile % km,
+            Distance::from_kilometers(0.609344).convert_to(DistanceUnit::Miles)
+        );
+
+        let mut total = km;
+        total += mile;
+        assert_eq!(total, Distance::from_kilometers(2.609344));
+        total -= km;
+        assert_eq!(total, Distance::from_kilometers(1.609344));
+        total *= 3.;
+        total /= 2.;
+        assert_eq!(total, Distance::from_kilometers(2.414016));
+        // Copying leaves the original usable
+        let copy = km;
+        assert_eq!(copy + km, Distance::from_meters(2000.));
+    }
+
+    #[test]
+    fn distance_min_max_clamp_abs() {
+        let short = Distance::from(100., DistanceUnit::Feet);
+        let long = Distance::from_meters(100.);
+        assert_eq!(short.min(long), short);
+        assert_eq!(short.max(long), long);
+        assert_eq!(long.min(short), short);
+        assert_eq!(
+            Distance::from_kilometers(5.).clamp(short, long),
+            Distance::from_meters(100.)
+        );
+        assert_eq!(Distance::from_meters(-5.).clamp(short, long), short);
+        assert_eq!(
+            Distance::from_meters(50.).clamp(short, long),
+            Distance::from_meters(50.)
+        );
+        assert_eq!(Distance::from_miles(-2.).abs(), Distance::from_miles(2.));
+    }
+
+    #[test]
+    #[should_panic]
+    fn distance_clamp_rejects_inverted_bounds() {
+        Distance::from_meters(1.).clamp(Distance::from_meters(2.), Distance::from_meters(1.));
+    }
+
+    #[test]
+    fn distance_sum() {
+        let legs = [
+            Distance::from_kilometers(1.),
+            Distance::from_meters(500.),
+            Distance::from_miles(1.),
+        ];
+        let total: Distance = legs.iter().sum();
+        assert_eq!(total, Distance::from_kilometers(3.109344));
+        assert_eq!(total.convert_to(DistanceUnit::Kilometers), total);
+        assert_eq!(legs.into_iter().sum::<Distance>(), total);
+
+        let route = [
+            Location::from(40.7885447, -111.7656248),
+            Location::from(40.7945846, -111.6950349),
+            Location::from(40.7599, -111.8910),
+        ];
+        let length: Distance = route.windows(2).map(|leg| leg[0].distance(&leg[1])).sum();
+        assert!(length.kilometers() > 20.);
+        assert_eq!(
+            core::iter::empty::<Distance>().sum::<Distance>(),
+            Distance::default()
+        );
+    }
+
+    // ==================== Speed Tests ====================
+
+    #[test]
+    fn speed_from_distance_and_duration() {
+        let speed = Distance::from_kilometers(90.) / Duration::from_secs(3600);
+        assert_eq!(speed.unit(), SpeedUnit::KilometersPerHour);
+        assert_eq!(speed, Speed::from_kilometers_per_hour(90.));
+        assert!((speed.meters_per_second() - 25.).abs() < 1e-9);
+
+        let sailing = Distance::from(12., DistanceUnit::NauticalMiles) / Duration::from_secs(7200);
+        assert_eq!(sailing.unit(), SpeedUnit::Knots);
+        assert_eq!(sailing, Speed::from_knots(6.));
+        assert_eq!(
+            (Distance::from_meters(100.) / Duration::from_millis(9580)).unit(),
+            SpeedUnit::MetersPerSecond
+        );
+
+        let covered = Speed::from_miles_per_hour(60.) * Duration::from_secs(90 * 60);
+        assert_eq!(
+            covered,
+            Distance::from_miles(90.).convert_to(DistanceUnit::Meters)
+        );
+        assert_eq!(
+            Duration::from_secs(10) * Speed::from_meters_per_second(3.),
+            Distance::from_meters(30.)
+        );
+
+        assert_eq!(
+            Speed::from_knots(10.).time_for(Distance::from(5., DistanceUnit::NauticalMiles)),
+            Some(Duration::from_secs(1800))
+        );
+        assert_eq!(Speed::default().time_for(Distance::from_meters(1.)), None);
+    }
+
+    #[test]
+    fn speed_conversions() {
+        let speed = Speed::from_knots(1.);
+        assert!((speed.kilometers_per_hour() - 1.852).abs() < 1e-12);
+        assert!(
+            (Speed::from_miles_per_hour(1.).in_unit(SpeedUnit::FeetPerSecond) - 22. / 15.).abs()
+                < 1e-12
+        );
+        assert!((Speed::from(1., SpeedUnit::Mach).kilometers_per_hour() - 1225.0584).abs() < 1e-9);
+        assert!(Speed::from_kilometers_per_hour(100.) > Speed::from_miles_per_hour(60.));
+        assert_eq!(
+            Speed::from_kilometers_per_hour(10.) + Speed::from_meters_per_second(1.),
+            Speed::from_kilometers_per_hour(13.6)
+        );
+        assert!(
+            (Speed::from_miles_per_hour(60.) / Speed::from_kilometers_per_hour(60.) - 1.609344)
+                .abs()
+                < 1e-12
+        );
+    }
+
+    #[test]
+    fn parse_speeds() {
+        assert_eq!("30km/h".parse(), Ok(Speed::from_kilometers_per_hour(30.)));
+        assert_eq!("12 knots".parse(), Ok(Speed::from_knots(12.)));
+        assert_eq!("12 kts".parse(), Ok(Speed::from_knots(12.)));
+        assert_eq!("-3.5 m/s".parse(), Ok(Speed::from_meters_per_second(-3.5)));
+        assert_eq!(
+            "1,100 ft/s".parse(),
+            Ok(Speed::from(1100., SpeedUnit::FeetPerSecond))
+        );
+        assert_eq!(
+            "65 miles per hour".parse(),
+            Ok(Speed::from_miles_per_hour(65.))
+        );
+        assert_eq!("Mach 0.8".parse(), Ok(Speed::from(0.8, SpeedUnit::Mach)));
+        assert_eq!("0.8 Ma".parse(), Ok(Speed::from(0.8, SpeedUnit::Mach)));
+        for unit in SpeedUnit::ALL {
+            assert_eq!(unit.abbreviation().parse(), Ok(unit));
+            assert_eq!(unit.name().parse(), Ok(unit));
+        }
+
+        assert_eq!("".parse::<Speed>(), Err(ParseSpeedError::Empty));
+        assert_eq!(
+            "12".parse::<Speed>(),
+            Err(ParseSpeedError::MissingUnit { position: 2 })
+        );
+        assert_eq!(
+            "12 furlongs per fortnight".parse::<Speed>(),
+            Err(ParseSpeedError::UnknownUnit { position: 3 })
+        );
+        assert_eq!(
+            "fast".parse::<Speed>(),
+            Err(ParseSpeedError::InvalidNumber { position: 0 })
+        );
+        assert_eq!(
+            "Mach fast".parse::<Speed>(),
+            Err(ParseSpeedError::InvalidNumber { position: 5 })
+        );
+        assert_eq!(
+            "abcé".parse::<Speed>(),
+            Err(ParseSpeedError::InvalidNumber { position: 0 })
+        );
+        assert_eq!(
+            "10 é".parse::<Speed>(),
+            Err(ParseSpeedError::UnknownUnit { position: 3 })
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn speed_display_round_trip() {
+        assert_eq!(Speed::from_knots(12.).to_string(), "12.0kn");
+        assert_eq!(
+            Speed::from_kilometers_per_hour(95.25).to_string(),
+            "95.2km/h"
+        );
+        for unit in SpeedUnit::ALL {
+            let speed = Speed::from(42.5, unit);
+            assert_eq!(speed.to_string().parse::<Speed>().unwrap(), speed);
+        }
+    }
+
+    #[test]
+    fn nmea_speed_accessors() {
+        let rmc = match parse_nmea(
+            "$GPRMC,123519,A,4807.038,N,01131.000,E,022.4,084.4,230394,003.1,W*6A",
+        ) {
+            Ok(NmeaSentence::Rmc(rmc)) => rmc,
+            other => panic!("unexpected {:?}", other),
+        };
+        assert_eq!(rmc.speed(), Some(Speed::from_knots(22.4)));
+
+        let vtg = Vtg {
+            course_true: None,
+            course_magnetic: None,
+            speed_knots: None,
+            speed_kmh: Some(10.2),
+        };
+        assert_eq!(vtg.speed(), Some(Speed::from_kilometers_per_hour(10.2)));
+    }
+
+    // ==================== Track Tests ====================
+
+    /// Meters per 0.001 degrees of latitude on the crate's spherical Earth.
+    #[cfg(feature = "std")]
+    const MILLIDEGREE_METERS: f64 = 6_378_137. * core::f64::consts::PI / 180_000.;
+
+    /// Ten points heading north 0.001 degrees every 30 seconds, with a two minute stop in the middle.
+    #[cfg(feature = "std")]
+    fn sample_track() -> Track {
+        let start = Timestamp::from_utc(2024, 5, 1, 7, 0, 0.);
+        let mut points = Vec::new();
+        let mut seconds = 0.;
+        for i in 0..10 {
+            let step = if i > 5 { i - 1 } else { i.min(5) };
+            let mut point = TrackPoint::from(
+                Location::from(step as f64 * 0.001, 0.),
+                start.add_seconds(seconds),
+            );
+            point.elevation = Some([100., 104., 110., 108., 108., 108., 108., 101., 103., 106.][i]);
+            points.push(point);
+            seconds += if i == 5 { 120. } else { 30. };
+        }
+        Track::from(points)
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn track_distance_and_time() {
+        let track = sample_track();
+        assert!((track.distance().meters() - 8. * MILLIDEGREE_METERS).abs() < 1e-6);
+        assert_eq!(track.moving_distance(), track.distance());
+        assert_eq!(track.elapsed_time(), Duration::from_secs(8 * 30 + 120));
+        assert_eq!(track.moving_time(), Duration::from_secs(8 * 30));
+
+        let average = track.average_speed().unwrap();
+        assert!((average.meters_per_second() - 8. * MILLIDEGREE_METERS / 360.).abs() < 1e-9);
+        let moving = track.average_moving_speed().unwrap();
+        assert!((moving.meters_per_second() - 8. * MILLIDEGREE_METERS / 240.).abs() < 1e-9);
+        assert!(
+            (track.max_speed().unwrap().meters_per_second() - MILLIDEGREE_METERS / 30.).abs()
+                < 1e-9
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn track_segment_speeds_and_elevation() {
+        let mut track = sample_track();
+        let speeds = track.segment_speeds();
+        assert_eq!(speeds.len(), 9);
+        assert_eq!(speeds[5], Some(Speed::from_meters_per_second(0.)));
+        assert!((speeds[0].unwrap().meters_per_second() - MILLIDEGREE_METERS / 30.).abs() < 1e-9);
+
+        assert_eq!(track.elevation_gain(), Distance::from_meters(15.));
+        assert_eq!(track.elevation_loss(), Distance::from_meters(9.));
+        track.points[2].elevation = None;
+        assert_eq!(track.elevation_gain(), Distance::from_meters(13.));
+        assert_eq!(track.elevation_loss(), Distance::from_meters(7.));
+
+        // Raising the threshold above the walking pace leaves nothing moving
+        track.moving_threshold = Speed::from_kilometers_per_hour(20.);
+        assert_eq!(track.moving_time(), Duration::ZERO);
+        assert_eq!(track.average_moving_speed(), None);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn track_splits() {
+        let track = sample_track();
+        // Just under three steps, so rounding can't push a boundary into the next segment
+        let every = Distance::from_meters(3. * MILLIDEGREE_METERS - 1e-6);
+        let splits = track.splits(&every);
+        assert_eq!(splits.len(), 3);
+        assert_eq!(splits[0].distance, every);
+        assert!((splits[0].duration.as_secs_f64() - 90.).abs() < 1e-3);
+        // The second split includes the stop
+        assert!((splits[1].duration.as_secs_f64() - 210.).abs() < 1e-3);
+        assert!((splits[2].distance.meters() - 2. * MILLIDEGREE_METERS).abs() < 1e-5);
+        assert!((splits[2].duration.as_secs_f64() - 60.).abs() < 1e-3);
+
+        let per_km = track.splits(&Distance::from_kilometers(0.5));
+        assert_eq!(per_km.len(), 2);
+        let pace = per_km[0].pace(Distance::from_kilometers(1.)).unwrap();
+        let expected = 30. / MILLIDEGREE_METERS * 1000.;
+        assert!((pace.as_secs_f64() - expected).abs() < 1e-6);
+        let total: Duration = per_km.iter().map(|split| split.duration).sum();
+        assert!((total.as_secs_f64() - 360.).abs() < 1e-6);
+        assert!(track.splits(&Distance::from_meters(0.)).is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn track_edge_cases() {
+        let empty = Track::default();
+        assert_eq!(empty.distance(), Distance::default());
+        assert_eq!(empty.elapsed_time(), Duration::ZERO);
+        assert_eq!(empty.average_speed(), None);
+        assert_eq!(empty.max_speed(), None);
+        assert!(empty.splits(&Distance::from_meters(100.)).is_empty());
+
+        let time = Timestamp::from_unix_seconds(0.);
+        let same_time = Track::from(vec![
+            TrackPoint::from(Location::from(0., 0.), time),
+            TrackPoint::from(Location::from(0.001, 0.), time),
+        ]);
+        assert_eq!(same_time.segment_speeds(), vec![None]);
+        assert_eq!(same_time.max_speed(), None);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn gpx_segment_to_track() {
+        let gpx = Gpx::parse(
+            r#"<gpx><trk><trkseg>
+                <trkpt lat="0" lon="0"><ele>5</ele><time>2024-05-01T07:00:00Z</time></trkpt>
+                <trkpt lat="0.001" lon="0"></trkpt>
+                <trkpt lat="0.002" lon="0"><time>2024-05-01T07:01:00Z</time></trkpt>
+            </trkseg></trk></gpx>"#,
+        )
+        .unwrap();
+        let track = gpx.tracks[0].segments[0].to_track();
+        assert_eq!(track.points.len(), 2);
+        assert_eq!(track.points[0].elevation, Some(5.));
+        assert_eq!(track.elapsed_time(), Duration::from_secs(60));
+        assert!((track.distance().meters() - 2. * MILLIDEGREE_METERS).abs() < 1e-6);
+    }
+
+    // ==================== Track Cleaning Tests ====================
+
+    /// A track with one point per `interval` seconds at the given locations.
+    #[cfg(feature = "std")]
+    fn timed_track(locations: impl IntoIterator<Item = Location>, interval: f64) -> Track {
+        let start = Timestamp::from_utc(2024, 5, 1, 7, 0, 0.);
+        Track::from(
+            locations
+                .into_iter()
+                .enumerate()
+                .map(|(i, location)| {
+                    TrackPoint::from(location, start.add_seconds(i as f64 * interval))
+                })
+                .collect(),
+        )
+    }
+
+    /// Meters to degrees of latitude on the crate's spherical Earth.
+    #[cfg(feature = "std")]
+    fn meters_to_degrees(meters: f64) -> f64 {
+        meters / MILLIDEGREE_METERS * 0.001
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn remove_outliers_drops_jumps() {
+        // About 11 m every 10 seconds, with a 1 km jump in the middle
+        let mut locations: Vec<Location> = (0..10)
+            .map(|i| Location::from(i as f64 * 0.0001, 0.))
+            .collect();
+        locations[5].longitude = 0.01;
+        let track = timed_track(locations, 10.);
+        let cleaned = track.remove_outliers(Speed::from_meters_per_second(10.));
+        assert_eq!(cleaned.points.len(), 9);
+        assert!(cleaned
+            .points
+            .iter()
+            .all(|point| point.location.longitude == 0.));
+        assert_eq!(cleaned.points[5], track.points[6]);
+        assert!(cleaned.max_speed().unwrap() < Speed::from_meters_per_second(2.));
+
+        // Everything is kept when nothing is too fast
+        let generous = track.remove_outliers(Speed::from_kilometers_per_hour(1000.));
+        assert_eq!(generous.points.len(), 10);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn collapse_stationary_keeps_stop_ends() {
+        let stop = Location::from(10., 10.);
+        let drift = |meters: f64| Location::from(10. + meters_to_degrees(meters), 10.);
+        let locations = vec![
+            Location::from(9.999, 10.),
+            stop.clone(),
+            stop.clone(),
+            drift(2.),
+            drift(-3.),
+            drift(1.),
+            Location::from(10.001, 10.),
+            Location::from(10.001, 10.),
+        ];
+        let track = timed_track(locations, 30.);
+
+        let collapsed = track.collapse_stationary(&Distance::from_meters(5.));
+        let kept: Vec<&Timestamp> = collapsed.points.iter().map(|point| &point.time).collect();
+        let expected: Vec<&Timestamp> = [0, 1, 5, 6, 7]
+            .iter()
+            .map(|&i| &track.points[i].time)
+            .collect();
+        assert_eq!(kept, expected);
+        assert_eq!(collapsed.elapsed_time(), track.elapsed_time());
+
+        let duplicates = track.collapse_stationary(&Distance::from_meters(0.));
+        assert_eq!(duplicates.points.len(), 8);
+        assert_eq!(
+            timed_track(vec![stop.clone(), stop.clone(), stop], 1.)
+                .collapse_stationary(&Distance::default())
+                .points
+                .len(),
+            2
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn moving_average_smooths_zigzag() {
+        let zigzag = |i: usize| {
+            Location::from(
+                i as f64 * 0.0001,
+                if i.is_multiple_of(2) { 0.0001 } else { -0.0001 },
+            )
+        };
+        let track = timed_track((0..11).map(zigzag), 5.);
+        let smoothed = track.moving_average(3);
+        assert_eq!(smoothed.points.len(), 11);
+        // The ends have no neighbours on one side, so stay put
+        assert_eq!(smoothed.points[0], track.points[0]);
+        for point in &smoothed.points[1..10] {
+            assert!((point.location.longitude.abs() - 0.0001 / 3.).abs() < 1e-9);
+        }
+        // Latitude moves linearly, so averaging leaves it unchanged
+        assert!((smoothed.points[4].location.latitude - 0.0004).abs() < 1e-12);
+        assert!(smoothed.distance() < track.distance());
+        assert_eq!(track.moving_average(1), track);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn savitzky_golay_preserves_curves() {
+        let parabola = |i: usize| {
+            let x = i as f64 - 6.;
+            Location::from(i as f64 * 0.0001, 0.00001 * x * x)
+        };
+        let track = timed_track((0..13).map(parabola), 1.);
+        let smoothed = track.savitzky_golay(5, 2);
+        for (a, b) in smoothed.points.iter().zip(&track.points) {
+            assert!((a.location.latitude - b.location.latitude).abs() < 1e-10);
+            assert!((a.location.longitude - b.location.longitude).abs() < 1e-10);
+        }
+        // A moving average flattens the bottom of the curve instead
+        let averaged = track.moving_average(5);
+        assert!(averaged.points[6].location.longitude > 0.00001);
+
+        let mut noisy = track.clone();
+        for (i, point) in noisy.points.iter_mut().enumerate() {
+            point.elevation = Some(100. + if i.is_multiple_of(2) { 2. } else { -2. });
+        }
+        let smoothed = noisy.savitzky_golay(5, 2);
+        let swing =
+            |track: &Track| track.elevation_gain().meters() + track.elevation_loss().meters();
+        assert!(swing(&smoothed) < swing(&noisy) / 2.);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn kalman_reduces_noise() {
+        // Heading north at 5 m/s, with fixes scattered up to 8 m either side
+        let truth = |i: usize| meters_to_degrees(i as f64 * 5.);
+        let noise = |i: usize| meters_to_degrees(8. * (i as f64 * 2.3).sin());
+        let track = timed_track(
+            (0..120).map(|i| Location::from(truth(i) + noise(i), noise(i + 1000))),
+            1.,
+        );
+        let filtered = track.kalman(&Distance::from_meters(8.), 0.5);
+        assert_eq!(filtered.points.len(), track.points.len());
+
+        let error = |track: &Track| {
+            let total: f64 = track.points[20..]
+                .iter()
+                .enumerate()
+                .map(|(i, point)| {
+                    point
+                        .location
+                        .distance(&Location::from(truth(i + 20), 0.))
+                        .meters()
+                        .powi(2)
+                })
+                .sum();
+            (total / (track.points.len() - 20) as f64).sqrt()
+        };
+        assert!(error(&filtered) < error(&track) / 2.);
+        let speed = filtered.segment_speeds()[100].unwrap().meters_per_second();
+        assert!((speed - 5.).abs() < 2.5);
+        assert_eq!(
+            Track::default().kalman(&Distance::from_meters(5.), 1.),
+            Track::default()
+        );
+    }
+
+    // ==================== Stop Detection Tests ====================
+
+    /// Drives north, stops for ten minutes, drives, pauses for three minutes, then drives
+    /// and stops for six minutes at the end. Stops drift by a few meters, one fix a minute.
+    #[cfg(feature = "std")]
+    fn delivery_track() -> Track {
+        let start = Timestamp::from_utc(2024, 5, 1, 9, 0, 0.);
+        let mut points = Vec::new();
+        let (mut latitude, mut seconds) = (0., 0.);
+        let mut push = |latitude: f64, seconds: f64| {
+            points.push(TrackPoint::from(
+                Location::from(latitude, 0.),
+                start.add_seconds(seconds),
+            ))
+        };
+        for (drive, stop_minutes) in [(5, 10), (4, 3), (3, 6)] {
+            for _ in 0..drive {
+                latitude += 0.002;
+                seconds += 30.;
+                push(latitude, seconds);
+            }
+            for minute in 1..=stop_minutes {
+                let drift = meters_to_degrees(if minute % 2 == 1 { 4. } else { -3. });
+                seconds += 60.;
+                push(latitude + drift, seconds);
+            }
+        }
+        Track::from(points)
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn detects_stops() {
+        let track = delivery_track();
+        let stops = track.stops(&Distance::from_meters(25.), Duration::from_secs(5 * 60));
+        assert_eq!(stops.len(), 2);
+
+        let first = &stops[0];
+        assert_eq!(first.members, 4..15);
+        assert_eq!(first.arrival, track.points[4].time);
+        assert_eq!(first.departure, track.points[14].time);
+        assert_eq!(first.duration(), Duration::from_secs(600));
+        assert!(first.centre.distance(&Location::from(0.01, 0.)).meters() < 5.);
+
+        // The three minute pause is too short, and the final stop runs to the end
+        let last = &stops[1];
+        assert_eq!(last.members, 24..track.points.len());
+        assert_eq!(last.duration(), Duration::from_secs(360));
+
+        let shorter = track.stops(&Distance::from_meters(25.), Duration::from_secs(2 * 60));
+        assert_eq!(shorter.len(), 3);
+        assert_eq!(shorter[1].members, 18..22);
+        // A radius smaller than the drift finds nothing
+        assert!(track
+            .stops(&Distance::from_meters(2.), Duration::from_secs(60))
+            .is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn splits_trips_between_stops() {
+        let track = delivery_track();
+        let stops = track.stops(&Distance::from_meters(25.), Duration::from_secs(5 * 60));
+        let trips = track.trips(&stops);
+        // Before the first stop, and between the two; nothing follows the last stop
+        assert_eq!(trips.len(), 2);
+        assert_eq!(trips[0].points, track.points[0..=4].to_vec());
+        assert_eq!(trips[1].points.first(), track.points.get(14));
+        assert_eq!(trips[1].points.last(), track.points.get(24));
+        assert_eq!(trips[1].points.len(), 11);
+
+        let total: Distance = trips.iter().map(Track::distance).sum();
+        assert!((total.meters() - 0.022 / 0.001 * MILLIDEGREE_METERS).abs() < 20.);
+
+        assert_eq!(track.trips(&[]), vec![track.clone()]);
+        assert!(Track::default().trips(&[]).is_empty());
+        assert!(Track::default()
+            .stops(&Distance::from_meters(10.), Duration::ZERO)
+            .is_empty());
+    }
+
+    // ==================== Geofence Tests ====================
+
+    /// A location the given number of meters north and east of the origin.
+    fn offset(north: f64, east: f64) -> Location {
+        let degrees = 180. / (6_378_137. * core::f64::consts::PI);
+        Location::from(north * degrees, east * degrees)
+    }
+
+    /// Feeds `(north, east, seconds)` updates for one device and records up to 16 events.
+    fn run_monitor(
+        monitor: &GeofenceMonitor,
+        states: &mut [FenceState],
+        updates: &[(f64, f64, f64)],
+    ) -> ([Option<GeofenceEvent>; 16], usize) {
+        let mut events = [None; 16];
+        let mut count = 0;
+        for &(north, east, seconds) in updates {
+            let time = Timestamp::from_unix_seconds(seconds);
+            monitor.update(states, &offset(north, east), time, |event| {
+                events[count] = Some(event);
+                count += 1;
+            });
+        }
+        (events, count)
+    }
+
+    fn event(fence: usize, kind: GeofenceEventKind, seconds: f64) -> Option<GeofenceEvent> {
+        Some(GeofenceEvent {
+            fence,
+            kind,
+            time: Timestamp::from_unix_seconds(seconds),
+        })
+    }
+
+    #[test]
+    fn geofence_contains() {
+        let circle = Geofence::Circle(Circle::from(offset(0., 0.), Distance::from_meters(100.)));
+        assert!(circle.contains(&offset(99., 0.)));
+        assert!(!circle.contains(&offset(0., 101.)));
+
+        let square = [
+            offset(0., 0.),
+            offset(0., 200.),
+            offset(200., 200.),
+            offset(200., 0.),
+        ];
+        let polygon = Geofence::Polygon(&square);
+        assert!(polygon.contains(&offset(100., 100.)));
+        assert!(polygon.contains(&offset(1., 199.)));
+        assert!(!polygon.contains(&offset(-1., 100.)));
+        assert!(!Geofence::Polygon(&[]).contains(&offset(0., 0.)));
+    }
+
+    #[test]
+    fn geofence_enter_exit_with_hysteresis() {
+        use GeofenceEventKind::*;
+        let fences = [Geofence::Circle(Circle::from(
+            offset(0., 0.),
+            Distance::from_meters(100.),
+        ))];
+        let monitor = GeofenceMonitor::new(
+            &fences,
+            Distance::from_meters(20.),
+            Duration::from_secs(600),
+        );
+        let mut states = [FenceState::new(); 1];
+
+        // Noise around the edge only enters once, then the device leaves properly
+        let (events, count) = run_monitor(
+            &monitor,
+            &mut states,
+            &[
+                (150., 0., 0.),
+                (98., 0., 10.),
+                (110., 0., 20.),
+                (95., 0., 30.),
+                (115., 0., 40.),
+                (130., 0., 50.),
+                (140., 0., 60.),
+            ],
+        );
+        assert_eq!(count, 2);
+        assert_eq!(events[0], event(0, Enter, 10.));
+        assert_eq!(events[1], event(0, Exit, 50.));
+        assert!(!states[0].is_inside());
+
+        // Without hysteresis every crossing is reported
+        let twitchy = GeofenceMonitor::new(&fences, Distance::default(), Duration::from_secs(600));
+        let mut states = [FenceState::new(); 1];
+        let (_, count) = run_monitor(
+            &twitchy,
+            &mut states,
+            &[
+                (98., 0., 10.),
+                (110., 0., 20.),
+                (95., 0., 30.),
+                (115., 0., 40.),
+            ],
+        );
+        assert_eq!(count, 4);
+    }
+
+    #[test]
+    fn geofence_dwell_and_multiple_devices() {
+        use GeofenceEventKind::*;
+        let square = [
+            offset(0., 0.),
+            offset(0., 200.),
+            offset(200., 200.),
+            offset(200., 0.),
+        ];
+        let fences = [
+            Geofence::Circle(Circle::from(offset(0., 0.), Distance::from_meters(50.))),
+            Geofence::Polygon(&square),
+        ];
+        let monitor = GeofenceMonitor::new(
+            &fences,
+            Distance::from_meters(10.),
+            Duration::from_secs(300),
+        );
+        let mut truck = [FenceState::new(); 2];
+        let mut van = [FenceState::new(); 2];
+
+        let (events, count) = run_monitor(
+            &monitor,
+            &mut truck,
+            &[
+                (-100., 100., 0.),
+                (100., 100., 60.),
+                (120., 90., 300.),
+                (110., 110., 400.),
+            ],
+        );
+        assert_eq!(count, 2);
+        assert_eq!(events[0], event(1, Enter, 60.));
+        assert_eq!(events[1], event(1, Dwell, 400.));
+        assert_eq!(truck[1].entered(), Some(Timestamp::from_unix_seconds(60.)));
+
+        // A second device is tracked independently, and can be in both fences
+        let (events, count) =
+            run_monitor(&monitor, &mut van, &[(10., 10., 100.), (-80., -80., 500.)]);
+        assert_eq!(count, 4);
+        assert_eq!(events[0], event(0, Enter, 100.));
+        assert_eq!(events[1], event(1, Enter, 100.));
+        assert_eq!(events[2], event(0, Exit, 500.));
+        assert_eq!(events[3], event(1, Exit, 500.));
+        assert!(truck[1].is_inside());
+
+        // A zero dwell time fires straight after entering
+        let instant = GeofenceMonitor::new(&fences[..1], Distance::default(), Duration::ZERO);
+        let mut states = [FenceState::default()];
+        let (events, count) = run_monitor(&instant, &mut states, &[(0., 0., 0.), (1., 1., 5.)]);
+        assert_eq!(count, 2);
+        assert_eq!(events[1], event(0, Dwell, 0.));
+    }
+
+    #[test]
+    #[should_panic]
+    fn geofence_monitor_rejects_missing_states() {
+        let fences = [
+            Geofence::Circle(Circle::from(offset(0., 0.), Distance::from_meters(50.))),
+            Geofence::Circle(Circle::from(offset(500., 0.), Distance::from_meters(50.))),
+        ];
+        let monitor = GeofenceMonitor::new(&fences, Distance::default(), Duration::ZERO);
+        let mut states = [FenceState::new()];
+        monitor.update(
+            &mut states,
+            &offset(500., 0.),
+            Timestamp::from_unix_seconds(0.),
+            |_| {},
+        );
+    }
 }
@@ -1,20 +1,11 @@
 use core::f64::consts::PI;
-#[cfg(feature = "std")]
 use core::fmt;
-#[cfg(feature = "std")]
-use lazy_static::lazy_static;
 use libm::{atan2, cos, fmod, pow, sin, sqrt};
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
 use crate::measurement::Distance;
 
-#[cfg(feature = "std")]
-lazy_static! {
-    pub static ref RADIUS_OF_EARTH: Distance = Distance::from_kilometers(6378.137);
-}
-
-#[cfg(not(feature = "std"))]
 pub static RADIUS_OF_EARTH: Distance = Distance::from_kilometers(6378.137);
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -26,6 +17,33 @@ pub enum Direction {
     West,
 }
 
+/// An error produced when validating coordinates with `Location::new`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LocationError {
+    /// The latitude wasn't between -90 and 90 degrees.
+    LatitudeOutOfRange { latitude: f64 },
+    /// The longitude wasn't between -180 and 180 degrees.
+    LongitudeOutOfRange { longitude: f64 },
+    /// A coordinate was NaN or infinite.
+    NonFinite,
+}
+
+impl fmt::Display for LocationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LocationError::LatitudeOutOfRange { latitude } => {
+                write!(f, "latitude {} is outside -90 to 90", latitude)
+            }
+            LocationError::LongitudeOutOfRange { longitude } => {
+                write!(f, "longitude {} is outside -180 to 180", longitude)
+            }
+            LocationError::NonFinite => write!(f, "coordinates must be finite"),
+        }
+    }
+}
+
+impl core::error::Error for LocationError {}
+
 #[derive(Debug, Clone, PartialEq, Default)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Location {
@@ -73,6 +91,7 @@ fn normalize_coordinates(lat: f64, lng: f64) -> (f64, f64) {
 }
 
 impl Location {
+    /// Creates a Location without any validation. See `Location::new` for a checked version.
     pub fn from(latitude: f64, longitude: f64) -> Self {
         Self {
             latitude,
@@ -80,6 +99,20 @@ impl Location {
         }
     }
 
+    /// Creates a Location, checking the coordinates are finite and within range.
+    pub fn new(latitude: f64, longitude: f64) -> Result<Self, LocationError> {
+        if !latitude.is_finite() || !longitude.is_finite() {
+            return Err(LocationError::NonFinite);
+        }
+        if !(-90. ..=90.).contains(&latitude) {
+            return Err(LocationError::LatitudeOutOfRange { latitude });
+        }
+        if !(-180. ..=180.).contains(&longitude) {
+            return Err(LocationError::LongitudeOutOfRange { longitude });
+        }
+        Ok(Self::from(latitude, longitude))
+    }
+
     /// Creates a new Location with normalized coordinates.
     /// Latitude is clamped/wrapped to [-90, 90] with proper pole crossing handling.
     /// Longitude is wrapped to [-180, 180].
@@ -103,7 +136,7 @@ impl Location {
 
         let c = 2. * atan2(sqrt(a), sqrt(1. - a));
 
-        RADIUS_OF_EARTH.clone() * c
+        RADIUS_OF_EARTH * c
     }
 
     pub fn add(&self, distance: &Distance, direction: Direction) -> Self {
@@ -151,6 +184,14 @@ impl fmt::Display for Location {
     }
 }
 
+impl TryFrom<(f64, f64)> for Location {
+    type Error = LocationError;
+
+    fn try_from((latitude, longitude): (f64, f64)) -> Result<Self, Self::Error> {
+        Self::new(latitude, longitude)
+    }
+}
+
 pub fn find_center_point(locations: &[Location]) -> Location {
     let (total_lat, total_lng) = locations.iter().fold(
         (0.0, 0.0),
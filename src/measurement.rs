@@ -1,8 +1,10 @@
 use core::cmp::Ordering;
-#[cfg(feature = "std")]
 use core::fmt;
-use core::ops::{Add, Div, Mul, Sub};
-use libm::trunc;
+use core::hash::{Hash, Hasher};
+use core::iter::Sum;
+use core::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Rem, Sub, SubAssign};
+use core::str::FromStr;
+use libm::{fabs, trunc};
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
@@ -17,6 +19,24 @@ pub enum DistanceUnit {
     Feet,
     Yards,
     Miles,
+
+    /// US survey feet, slightly longer than the international foot and used by older land surveys.
+    UsSurveyFeet,
+    /// US survey miles, 5280 US survey feet.
+    UsSurveyMiles,
+    /// Gunter's chains, 66 feet.
+    Chains,
+    /// Furlongs, 10 chains.
+    Furlongs,
+
+    NauticalMiles,
+    /// International cables, a tenth of a nautical mile.
+    Cables,
+    Fathoms,
+
+    /// The distance light travels in a vacuum in one second.
+    LightSeconds,
+    AstronomicalUnits,
 }
 
 impl DistanceUnit {
@@ -30,11 +50,22 @@ impl DistanceUnit {
             DistanceUnit::Feet => 0.3048,
             DistanceUnit::Yards => 0.9144,
             DistanceUnit::Miles => 1609.344,
+
+            DistanceUnit::UsSurveyFeet => 1200. / 3937.,
+            DistanceUnit::UsSurveyMiles => 6_336_000. / 3937.,
+            DistanceUnit::Chains => 20.1168,
+            DistanceUnit::Furlongs => 201.168,
+
+            DistanceUnit::NauticalMiles => 1852.,
+            DistanceUnit::Cables => 185.2,
+            DistanceUnit::Fathoms => 1.8288,
+
+            DistanceUnit::LightSeconds => 299_792_458.,
+            DistanceUnit::AstronomicalUnits => 149_597_870_700.,
         }
     }
 
-    #[cfg(feature = "std")]
-    fn abbreviation(&self) -> String {
+    pub fn abbreviation(&self) -> &'static str {
         match self {
             DistanceUnit::Centimeters => "cm",
             DistanceUnit::Meters => "m",
@@ -44,13 +75,22 @@ impl DistanceUnit {
             DistanceUnit::Feet => "ft",
             DistanceUnit::Yards => "yd",
             DistanceUnit::Miles => "mi",
+
+            DistanceUnit::UsSurveyFeet => "ftUS",
+            DistanceUnit::UsSurveyMiles => "miUS",
+            DistanceUnit::Chains => "ch",
+            DistanceUnit::Furlongs => "fur",
+
+            DistanceUnit::NauticalMiles => "nmi",
+            DistanceUnit::Cables => "cb",
+            DistanceUnit::Fathoms => "ftm",
+
+            DistanceUnit::LightSeconds => "ls",
+            DistanceUnit::AstronomicalUnits => "au",
         }
-        .into()
     }
 
-    #[cfg(feature = "std")]
-    #[allow(dead_code)]
-    fn name(&self) -> String {
+    pub fn name(&self) -> &'static str {
         match self {
             DistanceUnit::Centimeters => "centimeters",
             DistanceUnit::Meters => "meters",
@@ -60,12 +100,108 @@ impl DistanceUnit {
             DistanceUnit::Feet => "feet",
             DistanceUnit::Yards => "yards",
             DistanceUnit::Miles => "miles",
+
+            DistanceUnit::UsSurveyFeet => "US survey feet",
+            DistanceUnit::UsSurveyMiles => "US survey miles",
+            DistanceUnit::Chains => "chains",
+            DistanceUnit::Furlongs => "furlongs",
+
+            DistanceUnit::NauticalMiles => "nautical miles",
+            DistanceUnit::Cables => "cables",
+            DistanceUnit::Fathoms => "fathoms",
+
+            DistanceUnit::LightSeconds => "light seconds",
+            DistanceUnit::AstronomicalUnits => "astronomical units",
+        }
+    }
+
+    /// The name for exactly one of this unit, like "foot" rather than "feet".
+    pub fn singular_name(&self) -> &'static str {
+        match self {
+            DistanceUnit::Centimeters => "centimeter",
+            DistanceUnit::Meters => "meter",
+            DistanceUnit::Kilometers => "kilometer",
+
+            DistanceUnit::Inches => "inch",
+            DistanceUnit::Feet => "foot",
+            DistanceUnit::Yards => "yard",
+            DistanceUnit::Miles => "mile",
+
+            DistanceUnit::UsSurveyFeet => "US survey foot",
+            DistanceUnit::UsSurveyMiles => "US survey mile",
+            DistanceUnit::Chains => "chain",
+            DistanceUnit::Furlongs => "furlong",
+
+            DistanceUnit::NauticalMiles => "nautical mile",
+            DistanceUnit::Cables => "cable",
+            DistanceUnit::Fathoms => "fathom",
+
+            DistanceUnit::LightSeconds => "light second",
+            DistanceUnit::AstronomicalUnits => "astronomical unit",
+        }
+    }
+
+    /// Alternative spellings accepted when parsing, besides the abbreviation and names.
+    fn aliases(&self) -> &'static [&'static str] {
+        match self {
+            DistanceUnit::Centimeters => &["centimetre", "centimetres"],
+            DistanceUnit::Meters => &["metre", "metres"],
+            DistanceUnit::Kilometers => &["kilometre", "kilometres", "kms"],
+            DistanceUnit::Inches => &["\""],
+            DistanceUnit::Feet => &["'"],
+            DistanceUnit::Yards => &["yds"],
+            DistanceUnit::Miles => &[],
+            DistanceUnit::UsSurveyFeet => &["survey foot", "survey feet"],
+            DistanceUnit::UsSurveyMiles => &["survey mile", "survey miles"],
+            DistanceUnit::Chains => &[],
+            DistanceUnit::Furlongs => &[],
+            DistanceUnit::NauticalMiles => &["nm"],
+            DistanceUnit::Cables => &[],
+            DistanceUnit::Fathoms => &[],
+            DistanceUnit::LightSeconds => &[],
+            DistanceUnit::AstronomicalUnits => &[],
+        }
+    }
+
+    /// Every unit, in declaration order.
+    pub const ALL: [DistanceUnit; 16] = [
+        DistanceUnit::Centimeters,
+        DistanceUnit::Meters,
+        DistanceUnit::Kilometers,
+        DistanceUnit::Inches,
+        DistanceUnit::Feet,
+        DistanceUnit::Yards,
+        DistanceUnit::Miles,
+        DistanceUnit::UsSurveyFeet,
+        DistanceUnit::UsSurveyMiles,
+        DistanceUnit::Chains,
+        DistanceUnit::Furlongs,
+        DistanceUnit::NauticalMiles,
+        DistanceUnit::Cables,
+        DistanceUnit::Fathoms,
+        DistanceUnit::LightSeconds,
+        DistanceUnit::AstronomicalUnits,
+    ];
+}
+
+/// An error produced when validating a value with `Distance::try_new`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DistanceError {
+    /// The value was NaN or infinite.
+    NonFinite,
+}
+
+impl fmt::Display for DistanceError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DistanceError::NonFinite => write!(f, "distance must be finite"),
         }
-        .into()
     }
 }
 
-#[derive(Debug, Clone)]
+impl core::error::Error for DistanceError {}
+
+#[derive(Debug, Clone, Copy)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Distance {
     unit: DistanceUnit,
@@ -77,6 +213,15 @@ impl Distance {
         Self { value, unit }
     }
 
+    /// Creates a Distance, checking the value is finite. `from` skips this check.
+    pub fn try_new(value: f64, unit: DistanceUnit) -> Result<Self, DistanceError> {
+        if value.is_finite() {
+            Ok(Self::from(value, unit))
+        } else {
+            Err(DistanceError::NonFinite)
+        }
+    }
+
     pub const fn from_kilometers(value: f64) -> Self {
         Self::from(value, DistanceUnit::Kilometers)
     }
@@ -92,7 +237,7 @@ impl Distance {
 
     pub fn convert_to(&self, unit: DistanceUnit) -> Self {
         if self.unit == unit {
-            self.clone()
+            *self
         } else {
             let ratio = self.unit.in_meters() / unit.in_meters();
             let new_value = self.value * ratio;
@@ -100,6 +245,10 @@ impl Distance {
         }
     }
 
+    pub fn unit(&self) -> DistanceUnit {
+        self.unit
+    }
+
     pub fn in_unit(&self, unit: DistanceUnit) -> f64 {
         self.convert_to(unit).value
     }
@@ -116,10 +265,128 @@ impl Distance {
     pub fn miles(&self) -> f64 {
         self.in_unit(DistanceUnit::Miles)
     }
+
+    /// The shorter of the two distances, keeping its unit.
+    pub fn min(self, other: Self) -> Self {
+        if other.meters() < self.meters() {
+            other
+        } else {
+            self
+        }
+    }
+
+    /// The longer of the two distances, keeping its unit.
+    pub fn max(self, other: Self) -> Self {
+        if other.meters() > self.meters() {
+            other
+        } else {
+            self
+        }
+    }
+
+    /// Restricts the distance to between `min` and `max`. Panics if `min` is longer than `max`, like `f64::clamp`.
+    pub fn clamp(self, min: Self, max: Self) -> Self {
+        assert!(
+            min.meters() <= max.meters(),
+            "min must not be longer than max"
+        );
+        self.max(min).min(max)
+    }
+
+    pub fn abs(self) -> Self {
+        Self::from(libm::fabs(self.value), self.unit)
+    }
+}
+
+/// How close two distances must be for `Distance::approx_eq`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Tolerance {
+    /// The difference must be no more than this distance.
+    Absolute(Distance),
+    /// The difference must be no more than this fraction of the larger magnitude, like `1e-9`.
+    Relative(f64),
+}
+
+impl Distance {
+    /// Compares the distances in meters, within an absolute or relative tolerance.
+    ///
+    /// Unlike `==`, the result doesn't depend on which unit either side is stored in.
+    pub fn approx_eq(&self, other: &Distance, tolerance: &Tolerance) -> bool {
+        let (a, b) = (self.meters(), other.meters());
+        let allowed = match tolerance {
+            Tolerance::Absolute(distance) => fabs(distance.meters()),
+            Tolerance::Relative(fraction) => fabs(*fraction) * fabs(a).max(fabs(b)),
+        };
+        fabs(a - b) <= allowed
+    }
+
+    /// The distance in meters with zero and NaN normalized, so equal distances share a bit pattern.
+    fn canonical_meters(&self) -> f64 {
+        let meters = self.meters();
+        if meters == 0. {
+            0.
+        } else if meters.is_nan() {
+            f64::NAN
+        } else {
+            meters
+        }
+    }
+
+    /// Strict equality: both distances are exactly the same number of meters, bit for bit.
+    ///
+    /// `-0 m` and `0 km` are equal, and so are any two NaNs. This is the equality used by `OrderedDistance`.
+    pub fn strict_eq(&self, other: &Distance) -> bool {
+        self.canonical_meters().to_bits() == other.canonical_meters().to_bits()
+    }
+
+    /// A total ordering by length in meters, with NaN sorting after everything else.
+    pub fn total_cmp(&self, other: &Distance) -> Ordering {
+        self.canonical_meters().total_cmp(&other.canonical_meters())
+    }
+}
+
+/// A `Distance` with a total order, strict equality and a hash, for sorting, `BinaryHeap`s and map keys.
+///
+/// `Distance` itself compares within a tolerance, which can't be hashed consistently,
+/// so wrap it in this when those traits are needed.
+#[derive(Debug, Clone, Copy, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct OrderedDistance(pub Distance);
+
+impl From<Distance> for OrderedDistance {
+    fn from(distance: Distance) -> Self {
+        Self(distance)
+    }
+}
+
+impl PartialEq for OrderedDistance {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.strict_eq(&other.0)
+    }
+}
+
+impl Eq for OrderedDistance {}
+
+impl PartialOrd for OrderedDistance {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for OrderedDistance {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0)
+    }
 }
 
-const APPROX_EQUAL_PLACES: u8 = 3;
-fn approx_equal(a: f64, b: f64, decimal_places: u8) -> bool {
+impl Hash for OrderedDistance {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.canonical_meters().to_bits().hash(state);
+    }
+}
+
+pub(crate) const APPROX_EQUAL_PLACES: u8 = 3;
+pub(crate) fn approx_equal(a: f64, b: f64, decimal_places: u8) -> bool {
     let factor = 10u32.pow(decimal_places as u32) as f64;
     let a = trunc(a * factor);
     let b = trunc(b * factor);
@@ -175,15 +442,241 @@ impl Div<f64> for Distance {
     }
 }
 
+impl Div for Distance {
+    type Output = f64;
+
+    /// The ratio between two distances, whatever their units.
+    fn div(self, divisor: Distance) -> f64 {
+        self.value / divisor.in_unit(self.unit)
+    }
+}
+
+impl Mul<Distance> for f64 {
+    type Output = Distance;
+
+    fn mul(self, distance: Distance) -> Distance {
+        distance * self
+    }
+}
+
+impl Rem for Distance {
+    type Output = Self;
+
+    fn rem(self, other: Self) -> Self {
+        Self::from(self.value % other.in_unit(self.unit), self.unit)
+    }
+}
+
+impl Neg for Distance {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self::from(-self.value, self.unit)
+    }
+}
+
+impl AddAssign for Distance {
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+
+impl SubAssign for Distance {
+    fn sub_assign(&mut self, other: Self) {
+        *self = *self - other;
+    }
+}
+
+impl MulAssign<f64> for Distance {
+    fn mul_assign(&mut self, multiplier: f64) {
+        *self = *self * multiplier;
+    }
+}
+
+impl DivAssign<f64> for Distance {
+    fn div_assign(&mut self, divisor: f64) {
+        *self = *self / divisor;
+    }
+}
+
+impl Sum for Distance {
+    /// Adds the distances in the first one's unit. An empty iterator sums to 0 m.
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.reduce(|total, distance| total + distance)
+            .unwrap_or_default()
+    }
+}
+
+impl<'a> Sum<&'a Distance> for Distance {
+    fn sum<I: Iterator<Item = &'a Distance>>(iter: I) -> Self {
+        iter.copied().sum()
+    }
+}
+
 impl Default for Distance {
     fn default() -> Self {
         Self::from_meters(0.)
     }
 }
 
-#[cfg(feature = "std")]
 impl fmt::Display for Distance {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "{:.1}{}", self.value, self.unit.abbreviation())
     }
 }
+
+/// An error produced while parsing a `Distance` or `DistanceUnit` from a string.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseDistanceError {
+    /// The input was empty or only whitespace.
+    Empty,
+    /// A number couldn't be parsed, or a number was expected but not found.
+    InvalidNumber { position: usize },
+    /// A number wasn't followed by a unit.
+    MissingUnit { position: usize },
+    /// The text after a number isn't a known unit.
+    UnknownUnit { position: usize },
+}
+
+impl fmt::Display for ParseDistanceError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseDistanceError::Empty => write!(f, "distance is empty"),
+            ParseDistanceError::InvalidNumber { position } => {
+                write!(f, "invalid number in distance at position {}", position)
+            }
+            ParseDistanceError::MissingUnit { position } => {
+                write!(f, "missing unit in distance at position {}", position)
+            }
+            ParseDistanceError::UnknownUnit { position } => {
+                write!(f, "unknown unit in distance at position {}", position)
+            }
+        }
+    }
+}
+
+impl core::error::Error for ParseDistanceError {}
+
+/// Compares unit names word by word, ignoring case, and treating hyphens and underscores as spaces.
+pub(crate) fn same_name(text: &str, name: &str) -> bool {
+    let split = |c: char| c.is_whitespace() || c == '-' || c == '_';
+    let mut a = text.split(split).filter(|word| !word.is_empty());
+    let mut b = name.split(split).filter(|word| !word.is_empty());
+    loop {
+        match (a.next(), b.next()) {
+            (None, None) => return true,
+            (Some(x), Some(y)) if x.eq_ignore_ascii_case(y) => {}
+            _ => return false,
+        }
+    }
+}
+
+/// Parses a number that may use commas as thousands separators, like `1,200.5`.
+pub(crate) fn parse_number(text: &str) -> Option<f64> {
+    let mut buffer = [0u8; 64];
+    let mut length = 0;
+    let bytes = text.as_bytes();
+    for (i, &byte) in bytes.iter().enumerate() {
+        if byte == b',' {
+            let digit_before = i > 0 && bytes[i - 1].is_ascii_digit();
+            let digit_after = bytes.get(i + 1).is_some_and(u8::is_ascii_digit);
+            if !digit_before || !digit_after {
+                return None;
+            }
+            continue;
+        }
+        *buffer.get_mut(length)? = byte;
+        length += 1;
+    }
+    core::str::from_utf8(&buffer[..length]).ok()?.parse().ok()
+}
+
+impl FromStr for DistanceUnit {
+    type Err = ParseDistanceError;
+
+    /// Parses an abbreviation like `nmi`, or a name like `nautical mile` or `Nautical-Miles`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.trim().is_empty() {
+            return Err(ParseDistanceError::Empty);
+        }
+        DistanceUnit::ALL
+            .into_iter()
+            .find(|unit| {
+                [unit.abbreviation(), unit.name(), unit.singular_name()]
+                    .iter()
+                    .chain(unit.aliases())
+                    .any(|name| same_name(s, name))
+            })
+            .ok_or(ParseDistanceError::UnknownUnit { position: 0 })
+    }
+}
+
+impl FromStr for Distance {
+    type Err = ParseDistanceError;
+
+    /// Parses a distance like `5km`, `3.2 mi`, `1,200 ft`, `12 nautical miles` or `-0.5m`.
+    ///
+    /// Several parts can be combined, as in `2 miles 300 yards` or `5'6"`. The result is in
+    /// the first part's unit, and a leading minus sign negates the whole distance.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let bytes = s.as_bytes();
+        let mut position = 0;
+        let skip_whitespace = |mut position: usize| {
+            while bytes.get(position).is_some_and(u8::is_ascii_whitespace) {
+                position += 1;
+            }
+            position
+        };
+
+        position = skip_whitespace(position);
+        if position == bytes.len() {
+            return Err(ParseDistanceError::Empty);
+        }
+        let negative = bytes[position] == b'-';
+        if matches!(bytes[position], b'-' | b'+') {
+            position = skip_whitespace(position + 1);
+        }
+
+        let mut total: Option<Distance> = None;
+        while position < bytes.len() {
+            let number_start = position;
+            while bytes
+                .get(position)
+                .is_some_and(|&b| b.is_ascii_digit() || b == b'.' || b == b',')
+            {
+                position += 1;
+            }
+            let value = parse_number(&s[number_start..position]).ok_or(
+                ParseDistanceError::InvalidNumber {
+                    position: number_start,
+                },
+            )?;
+
+            let unit_start = skip_whitespace(position);
+            position = unit_start;
+            while bytes.get(position).is_some_and(|b| !b.is_ascii_digit()) {
+                position += 1;
+            }
+            if unit_start == position {
+                return Err(ParseDistanceError::MissingUnit {
+                    position: unit_start,
+                });
+            }
+            let unit: DistanceUnit =
+                s[unit_start..position]
+                    .parse()
+                    .map_err(|_| ParseDistanceError::UnknownUnit {
+                        position: unit_start,
+                    })?;
+
+            let part = Distance::from(value, unit);
+            total = Some(match total {
+                Some(total) => total + part,
+                None => part,
+            });
+        }
+
+        let total = total.ok_or(ParseDistanceError::InvalidNumber { position })?;
+        Ok(if negative { -total } else { total })
+    }
+}
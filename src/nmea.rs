@@ -0,0 +1,478 @@
+use core::fmt;
+use core::str::Split;
+
+use crate::location::Location;
+use crate::speed::Speed;
+use crate::time::{days_in_month, Timestamp};
+
+/// An error produced while parsing an NMEA 0183 sentence.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NmeaError {
+    /// The sentence didn't start with `$`.
+    MissingStart,
+    /// The sentence had no `*hh` checksum.
+    MissingChecksum,
+    /// The checksum didn't match the contents of the sentence.
+    ChecksumMismatch { expected: u8, computed: u8 },
+    /// The sentence type isn't one of GGA, RMC, GLL, VTG or GSA.
+    UnsupportedSentence { kind: [u8; 3] },
+    /// A field was missing or couldn't be parsed. Fields are numbered from 1 after the address.
+    InvalidField { field: usize },
+}
+
+impl fmt::Display for NmeaError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            NmeaError::MissingStart => write!(f, "NMEA sentence must start with '$'"),
+            NmeaError::MissingChecksum => write!(f, "NMEA sentence has no checksum"),
+            NmeaError::ChecksumMismatch { expected, computed } => write!(
+                f,
+                "NMEA checksum mismatch: expected {:02X}, computed {:02X}",
+                expected, computed
+            ),
+            NmeaError::UnsupportedSentence { kind } => write!(
+                f,
+                "unsupported NMEA sentence {}{}{}",
+                kind[0] as char, kind[1] as char, kind[2] as char
+            ),
+            NmeaError::InvalidField { field } => write!(f, "invalid NMEA field {}", field),
+        }
+    }
+}
+
+impl core::error::Error for NmeaError {}
+
+/// A UTC time of day as sent in `hhmmss.ss` fields.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct NmeaTime {
+    pub hour: u32,
+    pub minute: u32,
+    pub second: f64,
+}
+
+/// A UTC date as sent in the `ddmmyy` field of RMC sentences.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct NmeaDate {
+    pub year: i64,
+    pub month: u32,
+    pub day: u32,
+}
+
+/// The GGA fix quality indicator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FixQuality {
+    Invalid,
+    Gps,
+    Dgps,
+    Pps,
+    RealTimeKinematic,
+    FloatRtk,
+    Estimated,
+    Manual,
+    Simulation,
+}
+
+/// The GSA fix type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FixType {
+    NoFix,
+    Fix2D,
+    Fix3D,
+}
+
+/// GGA: fix data including altitude and satellite count.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Gga {
+    pub time: Option<NmeaTime>,
+    pub location: Option<Location>,
+    pub fix_quality: FixQuality,
+    pub satellites: Option<u8>,
+    pub hdop: Option<f64>,
+    /// Altitude above mean sea level in meters.
+    pub altitude: Option<f64>,
+    /// Height of the geoid above the WGS84 ellipsoid in meters.
+    pub geoid_separation: Option<f64>,
+}
+
+/// RMC: the recommended minimum position, speed, course and date.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rmc {
+    pub time: Option<NmeaTime>,
+    /// False when the receiver flagged the data as void (`V`).
+    pub valid: bool,
+    pub location: Option<Location>,
+    pub speed_knots: Option<f64>,
+    /// Course over ground in degrees from true north.
+    pub course: Option<f64>,
+    pub date: Option<NmeaDate>,
+    /// Magnetic variation in degrees, negative when west.
+    pub magnetic_variation: Option<f64>,
+}
+
+/// GLL: a position with the time it was fixed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Gll {
+    pub location: Option<Location>,
+    pub time: Option<NmeaTime>,
+    pub valid: bool,
+}
+
+/// VTG: course and speed over ground.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Vtg {
+    pub course_true: Option<f64>,
+    pub course_magnetic: Option<f64>,
+    pub speed_knots: Option<f64>,
+    pub speed_kmh: Option<f64>,
+}
+
+/// GSA: fix type, satellites used and dilution of precision.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Gsa {
+    /// True when the receiver chooses between 2D and 3D automatically.
+    pub automatic: bool,
+    pub fix_type: FixType,
+    /// PRNs of the satellites used in the fix.
+    pub satellites: [Option<u16>; 12],
+    pub pdop: Option<f64>,
+    pub hdop: Option<f64>,
+    pub vdop: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum NmeaSentence {
+    Gga(Gga),
+    Rmc(Rmc),
+    Gll(Gll),
+    Vtg(Vtg),
+    Gsa(Gsa),
+}
+
+impl NmeaSentence {
+    /// The location reported by the sentence, if it carries one.
+    pub fn location(&self) -> Option<&Location> {
+        match self {
+            NmeaSentence::Gga(gga) => gga.location.as_ref(),
+            NmeaSentence::Rmc(rmc) => rmc.location.as_ref(),
+            NmeaSentence::Gll(gll) => gll.location.as_ref(),
+            NmeaSentence::Vtg(_) | NmeaSentence::Gsa(_) => None,
+        }
+    }
+}
+
+impl Rmc {
+    /// Speed over ground.
+    pub fn speed(&self) -> Option<Speed> {
+        self.speed_knots.map(Speed::from_knots)
+    }
+
+    /// Combines the date and time fields into a timestamp.
+    /// Two digit years are interpreted as 1980-2079.
+    pub fn timestamp(&self) -> Option<Timestamp> {
+        let (date, time) = (self.date?, self.time?);
+        Some(Timestamp::from_utc(
+            date.year,
+            date.month,
+            date.day,
+            time.hour,
+            time.minute,
+            time.second,
+        ))
+    }
+}
+
+impl Vtg {
+    /// Speed over ground, from the knots field or else the km/h one.
+    pub fn speed(&self) -> Option<Speed> {
+        self.speed_knots
+            .map(Speed::from_knots)
+            .or_else(|| self.speed_kmh.map(Speed::from_kilometers_per_hour))
+    }
+}
+
+struct Fields<'a> {
+    fields: Split<'a, char>,
+    index: usize,
+}
+
+impl<'a> Fields<'a> {
+    fn next(&mut self) -> (&'a str, usize) {
+        self.index += 1;
+        (self.fields.next().unwrap_or(""), self.index)
+    }
+
+    fn skip(&mut self, count: usize) {
+        for _ in 0..count {
+            self.next();
+        }
+    }
+
+    fn f64(&mut self) -> Result<Option<f64>, NmeaError> {
+        let (field, index) = self.next();
+        if field.is_empty() {
+            return Ok(None);
+        }
+        field
+            .parse()
+            .map(Some)
+            .map_err(|_| NmeaError::InvalidField { field: index })
+    }
+
+    fn u16(&mut self) -> Result<Option<u16>, NmeaError> {
+        let (field, index) = self.next();
+        if field.is_empty() {
+            return Ok(None);
+        }
+        field
+            .parse()
+            .map(Some)
+            .map_err(|_| NmeaError::InvalidField { field: index })
+    }
+
+    fn char(&mut self) -> (Option<u8>, usize) {
+        let (field, index) = self.next();
+        (field.bytes().next(), index)
+    }
+
+    /// Reads a status field where `A` means valid and `V` means void.
+    fn status(&mut self) -> Result<bool, NmeaError> {
+        match self.char() {
+            (Some(b'A'), _) => Ok(true),
+            (Some(b'V'), _) | (None, _) => Ok(false),
+            (_, index) => Err(NmeaError::InvalidField { field: index }),
+        }
+    }
+
+    fn time(&mut self) -> Result<Option<NmeaTime>, NmeaError> {
+        let (field, index) = self.next();
+        if field.is_empty() {
+            return Ok(None);
+        }
+        let invalid = NmeaError::InvalidField { field: index };
+        let digits = |range: core::ops::Range<usize>| {
+            field
+                .get(range)
+                .filter(|part| part.bytes().all(|b| b.is_ascii_digit()))
+                .and_then(|part| part.parse::<u32>().ok())
+                .ok_or(invalid)
+        };
+        let hour = digits(0..2)?;
+        let minute = digits(2..4)?;
+        let second: f64 = field.get(4..).unwrap_or("").parse().map_err(|_| invalid)?;
+        if hour > 23 || minute > 59 || !(0. ..61.).contains(&second) {
+            return Err(invalid);
+        }
+        Ok(Some(NmeaTime {
+            hour,
+            minute,
+            second,
+        }))
+    }
+
+    fn date(&mut self) -> Result<Option<NmeaDate>, NmeaError> {
+        let (field, index) = self.next();
+        if field.is_empty() {
+            return Ok(None);
+        }
+        let invalid = NmeaError::InvalidField { field: index };
+        if field.len() != 6 || !field.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(invalid);
+        }
+        let part = |range: core::ops::Range<usize>| field[range].parse::<u32>().unwrap_or(0);
+        let (day, month, year) = (part(0..2), part(2..4), part(4..6));
+        let year = if year < 80 { 2000 + year } else { 1900 + year };
+        if !(1..=12).contains(&month) || day < 1 || day > days_in_month(year as i64, month) {
+            return Err(invalid);
+        }
+        Ok(Some(NmeaDate {
+            year: year as i64,
+            month,
+            day,
+        }))
+    }
+
+    /// Reads a `ddmm.mmmm` or `dddmm.mmmm` value followed by its hemisphere, which must be at
+    /// most `limit` degrees.
+    fn coordinate(
+        &mut self,
+        positive: u8,
+        negative: u8,
+        limit: f64,
+    ) -> Result<Option<f64>, NmeaError> {
+        let (field, index) = self.next();
+        let (hemisphere, hemisphere_index) = self.char();
+        if field.is_empty() {
+            return Ok(None);
+        }
+        let value: f64 = field
+            .parse()
+            .map_err(|_| NmeaError::InvalidField { field: index })?;
+        if !value.is_finite() || value < 0. {
+            return Err(NmeaError::InvalidField { field: index });
+        }
+        let degrees = libm::trunc(value / 100.);
+        let minutes = value - degrees * 100.;
+        let decimal = degrees + minutes / 60.;
+        if minutes >= 60. || decimal > limit {
+            return Err(NmeaError::InvalidField { field: index });
+        }
+        match hemisphere {
+            Some(h) if h == positive => Ok(Some(decimal)),
+            Some(h) if h == negative => Ok(Some(-decimal)),
+            _ => Err(NmeaError::InvalidField {
+                field: hemisphere_index,
+            }),
+        }
+    }
+
+    fn location(&mut self) -> Result<Option<Location>, NmeaError> {
+        let latitude = self.coordinate(b'N', b'S', 90.)?;
+        let longitude = self.coordinate(b'E', b'W', 180.)?;
+        match (latitude, longitude) {
+            (Some(latitude), Some(longitude)) => Ok(Some(Location::from(latitude, longitude))),
+            _ => Ok(None),
+        }
+    }
+}
+
+/// Checks the `$...*hh` framing and checksum, returning the text between them.
+fn checked_body(sentence: &str) -> Result<&str, NmeaError> {
+    let sentence = sentence.trim_end_matches(['\r', '\n']);
+    let body = sentence.strip_prefix('$').ok_or(NmeaError::MissingStart)?;
+    let (body, checksum) = body.rsplit_once('*').ok_or(NmeaError::MissingChecksum)?;
+
+    if !checksum.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return Err(NmeaError::MissingChecksum);
+    }
+    let expected = u8::from_str_radix(checksum, 16).map_err(|_| NmeaError::MissingChecksum)?;
+    let computed = body.bytes().fold(0, |acc, byte| acc ^ byte);
+    if checksum.len() != 2 || expected != computed {
+        return Err(NmeaError::ChecksumMismatch { expected, computed });
+    }
+    Ok(body)
+}
+
+/// Parses a single NMEA 0183 sentence such as
+/// `$GPGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*47`.
+///
+/// Any talker ID (GP, GN, GL, ...) is accepted. The checksum is required and validated.
+/// Parsing doesn't allocate, so it works without the `std` feature.
+pub fn parse_nmea(sentence: &str) -> Result<NmeaSentence, NmeaError> {
+    let body = checked_body(sentence)?;
+    let mut fields = Fields {
+        fields: body.split(','),
+        index: 0,
+    };
+    let address = fields.fields.next().unwrap_or("").as_bytes();
+    let mut kind = [0u8; 3];
+    if address.len() >= 3 {
+        kind.copy_from_slice(&address[address.len() - 3..]);
+    }
+
+    let parsed = match &kind {
+        b"GGA" => {
+            let time = fields.time()?;
+            let location = fields.location()?;
+            let fix_quality = match fields.char() {
+                (Some(b'0'), _) | (None, _) => FixQuality::Invalid,
+                (Some(b'1'), _) => FixQuality::Gps,
+                (Some(b'2'), _) => FixQuality::Dgps,
+                (Some(b'3'), _) => FixQuality::Pps,
+                (Some(b'4'), _) => FixQuality::RealTimeKinematic,
+                (Some(b'5'), _) => FixQuality::FloatRtk,
+                (Some(b'6'), _) => FixQuality::Estimated,
+                (Some(b'7'), _) => FixQuality::Manual,
+                (Some(b'8'), _) => FixQuality::Simulation,
+                (_, index) => return Err(NmeaError::InvalidField { field: index }),
+            };
+            let satellites = fields.u16()?.map(|count| count.min(255) as u8);
+            let hdop = fields.f64()?;
+            let altitude = fields.f64()?;
+            fields.skip(1);
+            let geoid_separation = fields.f64()?;
+            NmeaSentence::Gga(Gga {
+                time,
+                location,
+                fix_quality,
+                satellites,
+                hdop,
+                altitude,
+                geoid_separation,
+            })
+        }
+        b"RMC" => {
+            let time = fields.time()?;
+            let valid = fields.status()?;
+            let location = fields.location()?;
+            let speed_knots = fields.f64()?;
+            let course = fields.f64()?;
+            let date = fields.date()?;
+            let variation = fields.f64()?;
+            let magnetic_variation = match (variation, fields.char()) {
+                (Some(variation), (Some(b'W'), _)) => Some(-variation),
+                (variation, _) => variation,
+            };
+            NmeaSentence::Rmc(Rmc {
+                time,
+                valid,
+                location,
+                speed_knots,
+                course,
+                date,
+                magnetic_variation,
+            })
+        }
+        b"GLL" => {
+            let location = fields.location()?;
+            let time = fields.time()?;
+            let valid = fields.status()?;
+            NmeaSentence::Gll(Gll {
+                location,
+                time,
+                valid,
+            })
+        }
+        b"VTG" => {
+            let course_true = fields.f64()?;
+            fields.skip(1);
+            let course_magnetic = fields.f64()?;
+            fields.skip(1);
+            let speed_knots = fields.f64()?;
+            fields.skip(1);
+            let speed_kmh = fields.f64()?;
+            NmeaSentence::Vtg(Vtg {
+                course_true,
+                course_magnetic,
+                speed_knots,
+                speed_kmh,
+            })
+        }
+        b"GSA" => {
+            let automatic = !matches!(fields.char(), (Some(b'M'), _));
+            let fix_type = match fields.char() {
+                (Some(b'2'), _) => FixType::Fix2D,
+                (Some(b'3'), _) => FixType::Fix3D,
+                (Some(b'1'), _) | (None, _) => FixType::NoFix,
+                (_, index) => return Err(NmeaError::InvalidField { field: index }),
+            };
+            let mut satellites = [None; 12];
+            for satellite in satellites.iter_mut() {
+                *satellite = fields.u16()?;
+            }
+            let pdop = fields.f64()?;
+            let hdop = fields.f64()?;
+            let vdop = fields.f64()?;
+            NmeaSentence::Gsa(Gsa {
+                automatic,
+                fix_type,
+                satellites,
+                pdop,
+                hdop,
+                vdop,
+            })
+        }
+        _ => return Err(NmeaError::UnsupportedSentence { kind }),
+    };
+
+    Ok(parsed)
+}
@@ -0,0 +1,141 @@
+use core::fmt;
+use libm::{pow, round};
+
+use crate::location::Location;
+
+/// An error produced while decoding an encoded polyline string.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PolylineError {
+    /// The string ended in the middle of a value, or a latitude had no matching longitude.
+    Truncated { position: usize },
+    /// A character outside of the polyline alphabet (`?` to `~`) was found.
+    InvalidCharacter { character: char, position: usize },
+    /// A value, or the running total of the deltas, doesn't fit into a 64 bit integer.
+    Overflow { position: usize },
+}
+
+impl fmt::Display for PolylineError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PolylineError::Truncated { position } => {
+                write!(f, "polyline is truncated at position {}", position)
+            }
+            PolylineError::InvalidCharacter {
+                character,
+                position,
+            } => write!(
+                f,
+                "invalid polyline character {:?} at position {}",
+                character, position
+            ),
+            PolylineError::Overflow { position } => {
+                write!(f, "polyline value overflows at position {}", position)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for PolylineError {}
+
+fn precision_factor(precision: u32) -> f64 {
+    pow(10., precision as f64)
+}
+
+fn encode_value(value: i64, output: &mut String) {
+    let mut value = ((value << 1) ^ (value >> 63)) as u64;
+    while value >= 0x20 {
+        output.push((((value & 0x1f) | 0x20) as u8 + 63) as char);
+        value >>= 5;
+    }
+    output.push((value as u8 + 63) as char);
+}
+
+/// The largest scaled coordinate, chosen so the difference of any two fits in an `i64`.
+const MAX_SCALED: f64 = (1u64 << 62) as f64;
+
+/// A coordinate as an integer at the given scale. Values too large for the precision are clamped,
+/// and NaN becomes zero, so the deltas between them can't overflow.
+fn scaled(value: f64, factor: f64) -> i64 {
+    round(value * factor).clamp(-MAX_SCALED, MAX_SCALED) as i64
+}
+
+/// Encodes locations using the Google encoded polyline algorithm.
+/// Use a precision of 5 for the classic Google Maps format and 6 for OSRM/Valhalla style polylines.
+pub fn encode_polyline(locations: &[Location], precision: u32) -> String {
+    let factor = precision_factor(precision);
+    let mut output = String::new();
+    let (mut prev_lat, mut prev_lng) = (0i64, 0i64);
+
+    for location in locations {
+        let lat = scaled(location.latitude, factor);
+        let lng = scaled(location.longitude, factor);
+
+        encode_value(lat - prev_lat, &mut output);
+        encode_value(lng - prev_lng, &mut output);
+
+        prev_lat = lat;
+        prev_lng = lng;
+    }
+
+    output
+}
+
+fn decode_value(encoded: &str, index: &mut usize) -> Result<i64, PolylineError> {
+    let bytes = encoded.as_bytes();
+    let mut result = 0u64;
+    let mut shift = 0;
+
+    loop {
+        let position = *index;
+        let byte = *bytes
+            .get(position)
+            .ok_or(PolylineError::Truncated { position })?;
+        if !(63..=126).contains(&byte) {
+            return Err(PolylineError::InvalidCharacter {
+                character: encoded[position..].chars().next().unwrap_or('\u{fffd}'),
+                position,
+            });
+        }
+        let chunk = (byte - 63) as u64;
+        // Only the low 4 bits of a 13th chunk still fit
+        if shift > 60 || (shift == 60 && chunk & 0x1f > 0xf) {
+            return Err(PolylineError::Overflow { position });
+        }
+
+        result |= (chunk & 0x1f) << shift;
+        shift += 5;
+        *index += 1;
+
+        if chunk < 0x20 {
+            break;
+        }
+    }
+
+    Ok(((result >> 1) as i64) ^ -((result & 1) as i64))
+}
+
+/// Adds the next delta to a running coordinate.
+fn accumulate(total: i64, encoded: &str, index: &mut usize) -> Result<i64, PolylineError> {
+    let position = *index;
+    total
+        .checked_add(decode_value(encoded, index)?)
+        .ok_or(PolylineError::Overflow { position })
+}
+
+/// Decodes a Google encoded polyline string into locations.
+/// The precision must match the one used when encoding (usually 5 or 6).
+pub fn decode_polyline(encoded: &str, precision: u32) -> Result<Vec<Location>, PolylineError> {
+    let factor = precision_factor(precision);
+    let mut locations = Vec::new();
+    let mut index = 0;
+    let (mut lat, mut lng) = (0i64, 0i64);
+
+    while index < encoded.len() {
+        lat = accumulate(lat, encoded, &mut index)?;
+        lng = accumulate(lng, encoded, &mut index)?;
+        locations.push(Location::from(lat as f64 / factor, lng as f64 / factor));
+    }
+
+    Ok(locations)
+}
@@ -0,0 +1,533 @@
+use crate::bounding_box::BoundingBox;
+use crate::location::Location;
+
+/// Children per node for both the dynamic and the packed tree.
+const NODE_SIZE: usize = 16;
+
+/// A plain rectangle in longitude/latitude degrees that never wraps.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Rect {
+    min_x: f64,
+    min_y: f64,
+    max_x: f64,
+    max_y: f64,
+}
+
+impl Rect {
+    const EMPTY: Rect = Rect {
+        min_x: f64::INFINITY,
+        min_y: f64::INFINITY,
+        max_x: f64::NEG_INFINITY,
+        max_y: f64::NEG_INFINITY,
+    };
+
+    fn from_bbox(bbox: &BoundingBox) -> Self {
+        Rect {
+            min_x: bbox.west,
+            min_y: bbox.south,
+            max_x: bbox.east,
+            max_y: bbox.north,
+        }
+    }
+
+    fn union(&self, other: &Rect) -> Rect {
+        Rect {
+            min_x: self.min_x.min(other.min_x),
+            min_y: self.min_y.min(other.min_y),
+            max_x: self.max_x.max(other.max_x),
+            max_y: self.max_y.max(other.max_y),
+        }
+    }
+
+    fn intersects(&self, other: &Rect) -> bool {
+        self.min_x <= other.max_x
+            && other.min_x <= self.max_x
+            && self.min_y <= other.max_y
+            && other.min_y <= self.max_y
+    }
+
+    fn area(&self) -> f64 {
+        (self.max_x - self.min_x) * (self.max_y - self.min_y)
+    }
+
+    fn centre(&self) -> (f64, f64) {
+        (
+            (self.min_x + self.max_x) / 2.,
+            (self.min_y + self.max_y) / 2.,
+        )
+    }
+}
+
+/// The non wrapping rectangles covering a box, split at the antimeridian if needed.
+fn rects(bbox: &BoundingBox) -> ([Rect; 2], usize) {
+    match bbox.split_antimeridian() {
+        (west, Some(east)) => ([Rect::from_bbox(&west), Rect::from_bbox(&east)], 2),
+        (whole, None) => ([Rect::from_bbox(&whole), Rect::EMPTY], 1),
+    }
+}
+
+/// Items crossing the antimeridian are stored as two entries. The eastern half is only
+/// reported when the western half missed the window, so each item is found once.
+fn should_report(bbox: &BoundingBox, eastern_half: bool, window: &[Rect]) -> bool {
+    if !eastern_half {
+        return true;
+    }
+    let western = Rect::from_bbox(&bbox.split_antimeridian().0);
+    !window.iter().any(|rect| rect.intersects(&western))
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Entry {
+    rect: Rect,
+    id: usize,
+    eastern_half: bool,
+}
+
+#[derive(Debug, Clone)]
+enum Node {
+    Leaf(Rect, Vec<Entry>),
+    Branch(Rect, Vec<Node>),
+}
+
+impl Node {
+    fn rect(&self) -> &Rect {
+        match self {
+            Node::Leaf(rect, _) | Node::Branch(rect, _) => rect,
+        }
+    }
+
+    fn update_rect(&mut self) {
+        match self {
+            Node::Leaf(rect, entries) => {
+                *rect = entries
+                    .iter()
+                    .fold(Rect::EMPTY, |rect, entry| rect.union(&entry.rect))
+            }
+            Node::Branch(rect, children) => {
+                *rect = children
+                    .iter()
+                    .fold(Rect::EMPTY, |rect, child| rect.union(child.rect()))
+            }
+        }
+    }
+
+    /// Sorts along the wider axis and moves the upper half into a new sibling.
+    fn split<E>(items: &mut Vec<E>, rect: &Rect, item_rect: impl Fn(&E) -> &Rect) -> Vec<E> {
+        if rect.max_x - rect.min_x >= rect.max_y - rect.min_y {
+            items.sort_by(|a, b| item_rect(a).centre().0.total_cmp(&item_rect(b).centre().0));
+        } else {
+            items.sort_by(|a, b| item_rect(a).centre().1.total_cmp(&item_rect(b).centre().1));
+        }
+        items.split_off(items.len() / 2)
+    }
+
+    /// Inserts an entry, returning a new sibling if this node had to be split.
+    fn insert(&mut self, entry: Entry) -> Option<Node> {
+        let mut sibling = match self {
+            Node::Leaf(rect, entries) => {
+                *rect = rect.union(&entry.rect);
+                entries.push(entry);
+                if entries.len() <= NODE_SIZE {
+                    return None;
+                }
+                Node::Leaf(Rect::EMPTY, Self::split(entries, rect, |e| &e.rect))
+            }
+            Node::Branch(rect, children) => {
+                *rect = rect.union(&entry.rect);
+                let enlargement = |child: &Node| {
+                    let rect = child.rect();
+                    (rect.union(&entry.rect).area() - rect.area(), rect.area())
+                };
+                let index = (0..children.len())
+                    .min_by(|&a, &b| {
+                        let (a, b) = (enlargement(&children[a]), enlargement(&children[b]));
+                        a.0.total_cmp(&b.0).then(a.1.total_cmp(&b.1))
+                    })
+                    .unwrap_or(0);
+
+                let child_sibling = children[index].insert(entry)?;
+                children.push(child_sibling);
+                if children.len() <= NODE_SIZE {
+                    return None;
+                }
+                Node::Branch(Rect::EMPTY, Self::split(children, rect, |c| c.rect()))
+            }
+        };
+        self.update_rect();
+        sibling.update_rect();
+        Some(sibling)
+    }
+
+    fn remove(&mut self, rect: &Rect, id: usize) -> bool {
+        let removed = match self {
+            Node::Leaf(_, entries) => {
+                let before = entries.len();
+                entries.retain(|entry| entry.id != id);
+                entries.len() != before
+            }
+            Node::Branch(_, children) => {
+                let mut removed = false;
+                for child in children.iter_mut() {
+                    if child.rect().intersects(rect) {
+                        removed |= child.remove(rect, id);
+                    }
+                }
+                children.retain(|child| match child {
+                    Node::Leaf(_, entries) => !entries.is_empty(),
+                    Node::Branch(_, children) => !children.is_empty(),
+                });
+                removed
+            }
+        };
+        if removed {
+            self.update_rect();
+        }
+        removed
+    }
+
+    /// Finds the id of an entry with exactly this rect that `matches` accepts.
+    fn find(&self, rect: &Rect, matches: &mut impl FnMut(usize) -> bool) -> Option<usize> {
+        match self {
+            Node::Leaf(_, entries) => entries
+                .iter()
+                .find(|entry| entry.rect == *rect && matches(entry.id))
+                .map(|entry| entry.id),
+            Node::Branch(_, children) => children
+                .iter()
+                .filter(|child| child.rect().intersects(rect))
+                .find_map(|child| child.find(rect, matches)),
+        }
+    }
+
+    fn visit<'a, T>(
+        &self,
+        window: &[Rect],
+        items: &'a [Option<(BoundingBox, T)>],
+        f: &mut impl FnMut(&'a BoundingBox, &'a T),
+    ) {
+        if !window.iter().any(|rect| rect.intersects(self.rect())) {
+            return;
+        }
+        match self {
+            Node::Leaf(_, entries) => {
+                for entry in entries {
+                    if !window.iter().any(|rect| rect.intersects(&entry.rect)) {
+                        continue;
+                    }
+                    if let Some((bbox, item)) = &items[entry.id] {
+                        if should_report(bbox, entry.eastern_half, window) {
+                            f(bbox, item);
+                        }
+                    }
+                }
+            }
+            Node::Branch(_, children) => {
+                for child in children {
+                    child.visit(window, items, f);
+                }
+            }
+        }
+    }
+}
+
+/// An R-tree of items keyed by bounding boxes or locations, supporting insertion,
+/// removal and window queries. Boxes and windows crossing the antimeridian are handled.
+#[derive(Debug, Clone)]
+pub struct RTree<T> {
+    root: Node,
+    items: Vec<Option<(BoundingBox, T)>>,
+    free: Vec<usize>,
+    len: usize,
+}
+
+impl<T> Default for RTree<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> RTree<T> {
+    pub fn new() -> Self {
+        Self {
+            root: Node::Leaf(Rect::EMPTY, Vec::new()),
+            items: Vec::new(),
+            free: Vec::new(),
+            len: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn insert(&mut self, bbox: BoundingBox, item: T) {
+        let id = match self.free.pop() {
+            Some(id) => {
+                self.items[id] = Some((bbox, item));
+                id
+            }
+            None => {
+                self.items.push(Some((bbox, item)));
+                self.items.len() - 1
+            }
+        };
+
+        let (rects, count) = rects(&bbox);
+        for (i, rect) in rects[..count].iter().enumerate() {
+            let entry = Entry {
+                rect: *rect,
+                id,
+                eastern_half: i == 1,
+            };
+            if let Some(sibling) = self.root.insert(entry) {
+                let root = core::mem::replace(&mut self.root, Node::Leaf(Rect::EMPTY, Vec::new()));
+                self.root = Node::Branch(root.rect().union(sibling.rect()), vec![root, sibling]);
+            }
+        }
+        self.len += 1;
+    }
+
+    pub fn insert_point(&mut self, location: &Location, item: T) {
+        self.insert(BoundingBox::from_location(location), item);
+    }
+
+    /// Removes an item that was inserted with exactly this bounding box and returns it.
+    pub fn remove(&mut self, bbox: &BoundingBox, item: &T) -> Option<T>
+    where
+        T: PartialEq,
+    {
+        let (rects, count) = rects(bbox);
+        let items = &self.items;
+        let id = self.root.find(
+            &rects[0],
+            &mut |id| matches!(&items[id], Some((b, i)) if b == bbox && i == item),
+        )?;
+
+        for rect in &rects[..count] {
+            self.root.remove(rect, id);
+        }
+        if let Node::Branch(_, children) = &mut self.root {
+            if children.len() <= 1 {
+                self.root = children
+                    .pop()
+                    .unwrap_or(Node::Leaf(Rect::EMPTY, Vec::new()));
+            }
+        }
+
+        self.free.push(id);
+        self.len -= 1;
+        self.items[id].take().map(|(_, item)| item)
+    }
+
+    /// Calls `f` once for every item whose box intersects `window`, without allocating.
+    pub fn visit<'a>(&'a self, window: &BoundingBox, mut f: impl FnMut(&'a BoundingBox, &'a T)) {
+        let (rects, count) = rects(window);
+        self.root.visit(&rects[..count], &self.items, &mut f);
+    }
+
+    /// Returns every item whose box intersects `window`, in no particular order.
+    pub fn search(&self, window: &BoundingBox) -> Vec<&T> {
+        let mut found = Vec::new();
+        self.visit(window, |_, item| found.push(item));
+        found
+    }
+}
+
+impl<T> FromIterator<(BoundingBox, T)> for RTree<T> {
+    fn from_iter<I: IntoIterator<Item = (BoundingBox, T)>>(iter: I) -> Self {
+        let mut tree = Self::new();
+        for (bbox, item) in iter {
+            tree.insert(bbox, item);
+        }
+        tree
+    }
+}
+
+impl<T> FromIterator<(Location, T)> for RTree<T> {
+    fn from_iter<I: IntoIterator<Item = (Location, T)>>(iter: I) -> Self {
+        let mut tree = Self::new();
+        for (location, item) in iter {
+            tree.insert_point(&location, item);
+        }
+        tree
+    }
+}
+
+/// The position of `(x, y)` along a Hilbert curve filling a 65536 x 65536 grid.
+fn hilbert(mut x: u32, mut y: u32) -> u64 {
+    const N: u32 = 1 << 16;
+    let mut d = 0u64;
+    let mut s = N / 2;
+    while s > 0 {
+        let rx = u32::from(x & s > 0);
+        let ry = u32::from(y & s > 0);
+        d += s as u64 * s as u64 * ((3 * rx) ^ ry) as u64;
+        if ry == 0 {
+            if rx == 1 {
+                x = N - 1 - x;
+                y = N - 1 - y;
+            }
+            core::mem::swap(&mut x, &mut y);
+        }
+        s /= 2;
+    }
+    d
+}
+
+/// A static R-tree packed along a Hilbert curve for read heavy workloads.
+///
+/// All storage is allocated once when the tree is built, and queries through
+/// `visit` don't allocate at all. Items can't be added or removed afterwards.
+#[derive(Debug, Clone)]
+pub struct PackedRTree<T> {
+    items: Vec<(BoundingBox, T)>,
+    /// Leaf entries followed by each level of parent nodes, root last.
+    rects: Vec<Rect>,
+    entries: Vec<(usize, bool)>,
+    /// The start of each level in `rects`, plus the total length.
+    levels: Vec<usize>,
+}
+
+impl<T> PackedRTree<T> {
+    pub fn new(items: Vec<(BoundingBox, T)>) -> Self {
+        let leaf_count = items.len()
+            + items
+                .iter()
+                .filter(|(bbox, _)| bbox.crosses_antimeridian())
+                .count();
+
+        let mut level_sizes = vec![leaf_count];
+        while *level_sizes.last().unwrap_or(&0) > 1 {
+            let size = level_sizes[level_sizes.len() - 1];
+            level_sizes.push(size.div_ceil(NODE_SIZE));
+        }
+        let mut levels = Vec::with_capacity(level_sizes.len() + 1);
+        levels.push(0);
+        for size in &level_sizes {
+            levels.push(levels[levels.len() - 1] + size);
+        }
+
+        let mut leaves: Vec<(Rect, usize, bool)> = Vec::with_capacity(leaf_count);
+        let mut extent = Rect::EMPTY;
+        for (id, (bbox, _)) in items.iter().enumerate() {
+            let (rects, count) = rects(bbox);
+            for (i, rect) in rects[..count].iter().enumerate() {
+                extent = extent.union(rect);
+                leaves.push((*rect, id, i == 1));
+            }
+        }
+
+        let scale = |value: f64, min: f64, max: f64| {
+            if max > min {
+                ((value - min) / (max - min) * 65535.) as u32
+            } else {
+                0
+            }
+        };
+        leaves.sort_by_cached_key(|(rect, _, _)| {
+            let (x, y) = rect.centre();
+            hilbert(
+                scale(x, extent.min_x, extent.max_x),
+                scale(y, extent.min_y, extent.max_y),
+            )
+        });
+
+        let mut rects = Vec::with_capacity(levels[levels.len() - 1]);
+        let mut entries = Vec::with_capacity(leaf_count);
+        for (rect, id, eastern_half) in leaves {
+            rects.push(rect);
+            entries.push((id, eastern_half));
+        }
+        for level in 1..level_sizes.len() {
+            for start in (levels[level - 1]..levels[level]).step_by(NODE_SIZE) {
+                let end = (start + NODE_SIZE).min(levels[level]);
+                let parent = rects[start..end]
+                    .iter()
+                    .fold(Rect::EMPTY, |parent, rect| parent.union(rect));
+                rects.push(parent);
+            }
+        }
+
+        Self {
+            items,
+            rects,
+            entries,
+            levels,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn items(&self) -> &[(BoundingBox, T)] {
+        &self.items
+    }
+
+    fn visit_node<'a>(
+        &'a self,
+        level: usize,
+        index: usize,
+        window: &[Rect],
+        f: &mut impl FnMut(&'a BoundingBox, &'a T),
+    ) {
+        let rect = &self.rects[self.levels[level] + index];
+        if !window.iter().any(|w| w.intersects(rect)) {
+            return;
+        }
+        if level == 0 {
+            let (id, eastern_half) = self.entries[index];
+            let (bbox, item) = &self.items[id];
+            if should_report(bbox, eastern_half, window) {
+                f(bbox, item);
+            }
+            return;
+        }
+        let size = self.levels[level] - self.levels[level - 1];
+        let start = index * NODE_SIZE;
+        for child in start..(start + NODE_SIZE).min(size) {
+            self.visit_node(level - 1, child, window, f);
+        }
+    }
+
+    /// Calls `f` once for every item whose box intersects `window`, without allocating.
+    pub fn visit<'a>(&'a self, window: &BoundingBox, mut f: impl FnMut(&'a BoundingBox, &'a T)) {
+        if self.items.is_empty() {
+            return;
+        }
+        let (rects, count) = rects(window);
+        let root = self.levels.len() - 2;
+        self.visit_node(root, 0, &rects[..count], &mut f);
+    }
+
+    /// Returns every item whose box intersects `window`, in no particular order.
+    pub fn search(&self, window: &BoundingBox) -> Vec<&T> {
+        let mut found = Vec::new();
+        self.visit(window, |_, item| found.push(item));
+        found
+    }
+}
+
+impl<T> FromIterator<(BoundingBox, T)> for PackedRTree<T> {
+    fn from_iter<I: IntoIterator<Item = (BoundingBox, T)>>(iter: I) -> Self {
+        Self::new(iter.into_iter().collect())
+    }
+}
+
+impl<T> FromIterator<(Location, T)> for PackedRTree<T> {
+    fn from_iter<I: IntoIterator<Item = (Location, T)>>(iter: I) -> Self {
+        Self::new(
+            iter.into_iter()
+                .map(|(location, item)| (BoundingBox::from_location(&location), item))
+                .collect(),
+        )
+    }
+}
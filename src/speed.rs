@@ -0,0 +1,410 @@
+use core::cmp::Ordering;
+use core::fmt;
+use core::ops::{Add, Div, Mul, Neg, Sub};
+use core::str::FromStr;
+use core::time::Duration;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::measurement::{
+    approx_equal, parse_number, same_name, Distance, DistanceUnit, APPROX_EQUAL_PLACES,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum SpeedUnit {
+    MetersPerSecond,
+    KilometersPerHour,
+    MilesPerHour,
+    /// Nautical miles per hour.
+    Knots,
+    FeetPerSecond,
+    /// Multiples of the speed of sound at sea level in the standard atmosphere, 340.294 m/s.
+    Mach,
+}
+
+impl SpeedUnit {
+    pub fn in_meters_per_second(&self) -> f64 {
+        match self {
+            SpeedUnit::MetersPerSecond => 1.,
+            SpeedUnit::KilometersPerHour => 1000. / 3600.,
+            SpeedUnit::MilesPerHour => 0.44704,
+            SpeedUnit::Knots => 1852. / 3600.,
+            SpeedUnit::FeetPerSecond => 0.3048,
+            SpeedUnit::Mach => 340.294,
+        }
+    }
+
+    pub fn abbreviation(&self) -> &'static str {
+        match self {
+            SpeedUnit::MetersPerSecond => "m/s",
+            SpeedUnit::KilometersPerHour => "km/h",
+            SpeedUnit::MilesPerHour => "mph",
+            SpeedUnit::Knots => "kn",
+            SpeedUnit::FeetPerSecond => "ft/s",
+            SpeedUnit::Mach => "Ma",
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            SpeedUnit::MetersPerSecond => "meters per second",
+            SpeedUnit::KilometersPerHour => "kilometers per hour",
+            SpeedUnit::MilesPerHour => "miles per hour",
+            SpeedUnit::Knots => "knots",
+            SpeedUnit::FeetPerSecond => "feet per second",
+            SpeedUnit::Mach => "Mach",
+        }
+    }
+
+    /// The name for exactly one of this unit, like "knot" rather than "knots".
+    pub fn singular_name(&self) -> &'static str {
+        match self {
+            SpeedUnit::MetersPerSecond => "meter per second",
+            SpeedUnit::KilometersPerHour => "kilometer per hour",
+            SpeedUnit::MilesPerHour => "mile per hour",
+            SpeedUnit::Knots => "knot",
+            SpeedUnit::FeetPerSecond => "foot per second",
+            SpeedUnit::Mach => "Mach",
+        }
+    }
+
+    /// Alternative spellings accepted when parsing, besides the abbreviation and names.
+    fn aliases(&self) -> &'static [&'static str] {
+        match self {
+            SpeedUnit::MetersPerSecond => &["mps", "metres per second", "metre per second"],
+            SpeedUnit::KilometersPerHour => &[
+                "kph",
+                "kmh",
+                "km/hr",
+                "kilometres per hour",
+                "kilometre per hour",
+            ],
+            SpeedUnit::MilesPerHour => &["mi/h"],
+            SpeedUnit::Knots => &["kt", "kts"],
+            SpeedUnit::FeetPerSecond => &["fps"],
+            SpeedUnit::Mach => &[],
+        }
+    }
+
+    /// Every unit, in declaration order.
+    pub const ALL: [SpeedUnit; 6] = [
+        SpeedUnit::MetersPerSecond,
+        SpeedUnit::KilometersPerHour,
+        SpeedUnit::MilesPerHour,
+        SpeedUnit::Knots,
+        SpeedUnit::FeetPerSecond,
+        SpeedUnit::Mach,
+    ];
+}
+
+/// The speed unit matching a distance unit, with the distance unit and seconds it's measured over.
+fn natural_unit(unit: DistanceUnit) -> (SpeedUnit, DistanceUnit, f64) {
+    match unit {
+        DistanceUnit::Kilometers => (
+            SpeedUnit::KilometersPerHour,
+            DistanceUnit::Kilometers,
+            3600.,
+        ),
+        DistanceUnit::Miles | DistanceUnit::UsSurveyMiles => {
+            (SpeedUnit::MilesPerHour, DistanceUnit::Miles, 3600.)
+        }
+        DistanceUnit::NauticalMiles => (SpeedUnit::Knots, DistanceUnit::NauticalMiles, 3600.),
+        DistanceUnit::Feet | DistanceUnit::UsSurveyFeet => {
+            (SpeedUnit::FeetPerSecond, DistanceUnit::Feet, 1.)
+        }
+        _ => (SpeedUnit::MetersPerSecond, DistanceUnit::Meters, 1.),
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Speed {
+    unit: SpeedUnit,
+    value: f64,
+}
+
+impl Speed {
+    pub const fn from(value: f64, unit: SpeedUnit) -> Self {
+        Self { value, unit }
+    }
+
+    pub const fn from_meters_per_second(value: f64) -> Self {
+        Self::from(value, SpeedUnit::MetersPerSecond)
+    }
+
+    pub const fn from_kilometers_per_hour(value: f64) -> Self {
+        Self::from(value, SpeedUnit::KilometersPerHour)
+    }
+
+    pub const fn from_miles_per_hour(value: f64) -> Self {
+        Self::from(value, SpeedUnit::MilesPerHour)
+    }
+
+    pub const fn from_knots(value: f64) -> Self {
+        Self::from(value, SpeedUnit::Knots)
+    }
+
+    /// The average speed covering `distance` in `duration`, the same as `distance / duration`.
+    ///
+    /// The unit follows the distance's: kilometers give km/h, miles mph, nautical miles knots,
+    /// feet ft/s and anything else m/s. A zero duration gives an infinite or NaN speed.
+    pub fn from_distance(distance: Distance, duration: Duration) -> Self {
+        let (speed_unit, distance_unit, seconds) = natural_unit(distance.unit());
+        let value = distance.in_unit(distance_unit) / (duration.as_secs_f64() / seconds);
+        Self::from(value, speed_unit)
+    }
+
+    pub fn unit(&self) -> SpeedUnit {
+        self.unit
+    }
+
+    pub fn convert_to(&self, unit: SpeedUnit) -> Self {
+        if self.unit == unit {
+            *self
+        } else {
+            let ratio = self.unit.in_meters_per_second() / unit.in_meters_per_second();
+            Self::from(self.value * ratio, unit)
+        }
+    }
+
+    pub fn in_unit(&self, unit: SpeedUnit) -> f64 {
+        self.convert_to(unit).value
+    }
+
+    pub fn meters_per_second(&self) -> f64 {
+        self.in_unit(SpeedUnit::MetersPerSecond)
+    }
+
+    pub fn kilometers_per_hour(&self) -> f64 {
+        self.in_unit(SpeedUnit::KilometersPerHour)
+    }
+
+    pub fn miles_per_hour(&self) -> f64 {
+        self.in_unit(SpeedUnit::MilesPerHour)
+    }
+
+    pub fn knots(&self) -> f64 {
+        self.in_unit(SpeedUnit::Knots)
+    }
+
+    /// How long it takes to cover `distance` at this speed, or `None` if it never arrives.
+    pub fn time_for(&self, distance: Distance) -> Option<Duration> {
+        Duration::try_from_secs_f64(distance.meters() / self.meters_per_second()).ok()
+    }
+}
+
+impl PartialEq for Speed {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        let a = self.in_unit(self.unit);
+        let b = other.in_unit(self.unit);
+        approx_equal(a, b, APPROX_EQUAL_PLACES)
+    }
+}
+
+impl PartialOrd for Speed {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.value.partial_cmp(&other.in_unit(self.unit))
+    }
+}
+
+impl Add for Speed {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self::from(self.value + other.in_unit(self.unit), self.unit)
+    }
+}
+
+impl Sub for Speed {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self::from(self.value - other.in_unit(self.unit), self.unit)
+    }
+}
+
+impl Mul<f64> for Speed {
+    type Output = Self;
+
+    fn mul(self, multiplier: f64) -> Self {
+        Self::from(self.value * multiplier, self.unit)
+    }
+}
+
+impl Div<f64> for Speed {
+    type Output = Self;
+
+    fn div(self, divisor: f64) -> Self {
+        Self::from(self.value / divisor, self.unit)
+    }
+}
+
+impl Div for Speed {
+    type Output = f64;
+
+    /// The ratio between two speeds, whatever their units.
+    fn div(self, divisor: Speed) -> f64 {
+        self.value / divisor.in_unit(self.unit)
+    }
+}
+
+impl Neg for Speed {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self::from(-self.value, self.unit)
+    }
+}
+
+impl Mul<Duration> for Speed {
+    type Output = Distance;
+
+    /// The distance covered at this speed, in meters.
+    fn mul(self, duration: Duration) -> Distance {
+        Distance::from_meters(self.meters_per_second() * duration.as_secs_f64())
+    }
+}
+
+impl Mul<Speed> for Duration {
+    type Output = Distance;
+
+    fn mul(self, speed: Speed) -> Distance {
+        speed * self
+    }
+}
+
+impl Div<Duration> for Distance {
+    type Output = Speed;
+
+    fn div(self, duration: Duration) -> Speed {
+        Speed::from_distance(self, duration)
+    }
+}
+
+impl Default for Speed {
+    fn default() -> Self {
+        Self::from_meters_per_second(0.)
+    }
+}
+
+impl fmt::Display for Speed {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:.1}{}", self.value, self.unit.abbreviation())
+    }
+}
+
+/// An error produced while parsing a `Speed` or `SpeedUnit` from a string.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseSpeedError {
+    /// The input was empty or only whitespace.
+    Empty,
+    /// The number couldn't be parsed, or wasn't found.
+    InvalidNumber { position: usize },
+    /// The number wasn't followed by a unit.
+    MissingUnit { position: usize },
+    /// The text after the number isn't a known unit.
+    UnknownUnit { position: usize },
+}
+
+impl fmt::Display for ParseSpeedError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseSpeedError::Empty => write!(f, "speed is empty"),
+            ParseSpeedError::InvalidNumber { position } => {
+                write!(f, "invalid number in speed at position {}", position)
+            }
+            ParseSpeedError::MissingUnit { position } => {
+                write!(f, "missing unit in speed at position {}", position)
+            }
+            ParseSpeedError::UnknownUnit { position } => {
+                write!(f, "unknown unit in speed at position {}", position)
+            }
+        }
+    }
+}
+
+impl core::error::Error for ParseSpeedError {}
+
+impl FromStr for SpeedUnit {
+    type Err = ParseSpeedError;
+
+    /// Parses an abbreviation like `km/h` or `kts`, or a name like `miles per hour`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.trim().is_empty() {
+            return Err(ParseSpeedError::Empty);
+        }
+        SpeedUnit::ALL
+            .into_iter()
+            .find(|unit| {
+                [unit.abbreviation(), unit.name(), unit.singular_name()]
+                    .iter()
+                    .chain(unit.aliases())
+                    .any(|name| same_name(s, name))
+            })
+            .ok_or(ParseSpeedError::UnknownUnit { position: 0 })
+    }
+}
+
+impl FromStr for Speed {
+    type Err = ParseSpeedError;
+
+    /// Parses a speed like `30km/h`, `12 knots`, `-3.5 m/s`, `1,100 ft/s` or `Mach 0.8`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let start = s.len() - s.trim_start().len();
+        let trimmed = s.trim();
+        if trimmed.is_empty() {
+            return Err(ParseSpeedError::Empty);
+        }
+
+        // Mach numbers are usually written before the value
+        let prefix = "mach";
+        let is_mach = trimmed
+            .get(..prefix.len())
+            .is_some_and(|start| start.eq_ignore_ascii_case(prefix));
+        if trimmed.len() > prefix.len() && is_mach {
+            let rest = &trimmed[prefix.len()..];
+            let position = start + prefix.len() + (rest.len() - rest.trim_start().len());
+            let value = rest
+                .trim()
+                .parse()
+                .map_err(|_| ParseSpeedError::InvalidNumber { position })?;
+            return Ok(Speed::from(value, SpeedUnit::Mach));
+        }
+
+        let bytes = s.as_bytes();
+        let negative = bytes[start] == b'-';
+        let number_start = if matches!(bytes[start], b'-' | b'+') {
+            start + 1
+        } else {
+            start
+        };
+        let mut position = number_start;
+        while bytes
+            .get(position)
+            .is_some_and(|&b| b.is_ascii_digit() || b == b'.' || b == b',')
+        {
+            position += 1;
+        }
+        let value =
+            parse_number(&s[number_start..position]).ok_or(ParseSpeedError::InvalidNumber {
+                position: number_start,
+            })?;
+        let value = if negative { -value } else { value };
+
+        let unit_start = position + (s[position..].len() - s[position..].trim_start().len());
+        if s[unit_start..].trim().is_empty() {
+            return Err(ParseSpeedError::MissingUnit {
+                position: unit_start,
+            });
+        }
+        let unit = s[unit_start..]
+            .parse()
+            .map_err(|_| ParseSpeedError::UnknownUnit {
+                position: unit_start,
+            })?;
+        Ok(Speed::from(value, unit))
+    }
+}
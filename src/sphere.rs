@@ -0,0 +1,182 @@
+// Some helpers are only used by the std-only spatial types
+#![cfg_attr(not(feature = "std"), allow(dead_code))]
+
+use core::f64::consts::PI;
+use libm::{asin, atan2, cos, fmod, sin, sqrt};
+
+use crate::location::{Location, RADIUS_OF_EARTH};
+use crate::measurement::Distance;
+
+/// A point on (or direction from the centre of) the unit sphere.
+/// Working with vectors avoids the special cases latitude and longitude have at the poles and antimeridian.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub(crate) struct Vector3 {
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+}
+
+impl Vector3 {
+    pub fn new(x: f64, y: f64, z: f64) -> Self {
+        Self { x, y, z }
+    }
+
+    pub fn from_location(location: &Location) -> Self {
+        let lat = location.latitude * PI / 180.;
+        let lng = location.longitude * PI / 180.;
+        Self::new(cos(lat) * cos(lng), cos(lat) * sin(lng), sin(lat))
+    }
+
+    /// Converts back to a location. The vector doesn't need to be normalized.
+    pub fn to_location(self) -> Location {
+        let latitude = asin((self.z / self.length()).clamp(-1., 1.));
+        let longitude = atan2(self.y, self.x);
+        Location::from(latitude * 180. / PI, longitude * 180. / PI)
+    }
+
+    pub fn dot(&self, other: &Vector3) -> f64 {
+        self.x * other.x + self.y * other.y + self.z * other.z
+    }
+
+    pub fn cross(&self, other: &Vector3) -> Vector3 {
+        Vector3::new(
+            self.y * other.z - self.z * other.y,
+            self.z * other.x - self.x * other.z,
+            self.x * other.y - self.y * other.x,
+        )
+    }
+
+    pub fn length(&self) -> f64 {
+        sqrt(self.dot(self))
+    }
+
+    pub fn add(&self, other: &Vector3) -> Vector3 {
+        Vector3::new(self.x + other.x, self.y + other.y, self.z + other.z)
+    }
+
+    pub fn sub(&self, other: &Vector3) -> Vector3 {
+        Vector3::new(self.x - other.x, self.y - other.y, self.z - other.z)
+    }
+
+    pub fn scale(&self, factor: f64) -> Vector3 {
+        Vector3::new(self.x * factor, self.y * factor, self.z * factor)
+    }
+
+    /// Returns the unit vector in the same direction, or `None` for a (near) zero vector.
+    pub fn normalize(&self) -> Option<Vector3> {
+        let length = self.length();
+        if length < 1e-12 {
+            None
+        } else {
+            Some(self.scale(1. / length))
+        }
+    }
+
+    /// The angle between two vectors in radians. Stable for both tiny and near antipodal angles.
+    pub fn angle(&self, other: &Vector3) -> f64 {
+        atan2(self.cross(other).length(), self.dot(other))
+    }
+}
+
+/// Longitude difference wrapped into (-180, 180].
+pub(crate) fn wrap_longitude(delta: f64) -> f64 {
+    let wrapped = fmod(fmod(delta, 360.) + 360., 360.);
+    if wrapped > 180. {
+        wrapped - 360.
+    } else {
+        wrapped
+    }
+}
+
+/// Converts a central angle in radians to a distance along the Earth's surface.
+pub(crate) fn arc_length(angle: f64) -> Distance {
+    RADIUS_OF_EARTH * angle
+}
+
+/// Converts a distance along the Earth's surface to a central angle in radians.
+pub(crate) fn central_angle(distance: &Distance) -> f64 {
+    distance.kilometers() / RADIUS_OF_EARTH.kilometers()
+}
+
+/// The angle from `point` to the shortest great circle arc between `a` and `b`.
+pub(crate) fn angle_to_arc(point: &Vector3, a: &Vector3, b: &Vector3) -> f64 {
+    if let Some(normal) = a.cross(b).normalize() {
+        // The closest point on the whole great circle is between a and b
+        if a.cross(point).dot(&normal) > 0. && point.cross(b).dot(&normal) > 0. {
+            return asin(point.dot(&normal).abs().min(1.));
+        }
+    }
+    point.angle(a).min(point.angle(b))
+}
+
+/// Whether the arcs `ab` and `cd` cross at a point interior to both.
+pub(crate) fn arcs_cross(a: &Vector3, b: &Vector3, c: &Vector3, d: &Vector3) -> bool {
+    let ab = a.cross(b);
+    let acb = -ab.dot(c);
+    let bda = ab.dot(d);
+    if acb * bda <= 0. {
+        return false;
+    }
+    let cd = c.cross(d);
+    let cbd = -cd.dot(b);
+    let dac = cd.dot(a);
+    acb * cbd > 0. && acb * dac > 0.
+}
+
+/// Whether `point` is inside a ring of vertices joined by great circle arcs, in either winding order.
+/// The ring must fit within a hemisphere.
+///
+/// Works by summing the angles the edges subtend at the point, which is about ±2π inside and 0 outside.
+/// Points inside the antipodal copy of the ring also wind ±2π, so those are ruled out by hemisphere.
+pub(crate) fn ring_contains(ring: &[Location], point: &Vector3) -> bool {
+    let mut total = 0.;
+    let mut sum = Vector3::default();
+    for (i, location) in ring.iter().enumerate() {
+        let a = Vector3::from_location(location);
+        let b = Vector3::from_location(&ring[(i + 1) % ring.len()]);
+        total += atan2(
+            point.dot(&a.cross(&b)),
+            a.dot(&b) - point.dot(&a) * point.dot(&b),
+        );
+        sum = sum.add(&a);
+    }
+    total.abs() > PI && point.dot(&sum) > 0.
+}
+
+/// The normalized mean of the locations' unit vectors, which handles the antimeridian and poles.
+/// Falls back to the first location when they cancel out, e.g. for two antipodal points.
+pub(crate) fn spherical_mean<'a>(
+    locations: impl IntoIterator<Item = &'a Location>,
+) -> Option<Location> {
+    let mut first = None;
+    let mut sum = Vector3::default();
+    for location in locations {
+        first.get_or_insert(location);
+        sum = sum.add(&Vector3::from_location(location));
+    }
+    match sum.normalize() {
+        Some(mean) => Some(mean.to_location()),
+        None => first.cloned(),
+    }
+}
+
+/// The initial bearing in degrees, clockwise from north, of the great circle from `from` to `to`.
+pub(crate) fn bearing(from: &Location, to: &Location) -> f64 {
+    let (lat1, lat2) = (from.latitude * PI / 180., to.latitude * PI / 180.);
+    let d_lng = (to.longitude - from.longitude) * PI / 180.;
+    let y = sin(d_lng) * cos(lat2);
+    let x = cos(lat1) * sin(lat2) - sin(lat1) * cos(lat2) * cos(d_lng);
+    atan2(y, x) * 180. / PI
+}
+
+/// The location reached by travelling a central angle of `angle` radians from `origin` along `bearing` degrees.
+pub(crate) fn destination(origin: &Location, bearing: f64, angle: f64) -> Location {
+    let lat = origin.latitude * PI / 180.;
+    let theta = bearing * PI / 180.;
+    let lat2 = asin((sin(lat) * cos(angle) + cos(lat) * sin(angle) * cos(theta)).clamp(-1., 1.));
+    let d_lng = atan2(
+        sin(theta) * sin(angle) * cos(lat),
+        cos(angle) - sin(lat) * sin(lat2),
+    );
+    Location::from_normalized(lat2 * 180. / PI, origin.longitude + d_lng * 180. / PI)
+}
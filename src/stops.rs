@@ -0,0 +1,87 @@
+use core::ops::Range;
+use core::time::Duration;
+
+use crate::location::Location;
+use crate::measurement::Distance;
+use crate::sphere::spherical_mean;
+use crate::time::Timestamp;
+use crate::track::Track;
+
+/// A place where the track stayed within a radius for a while, from `Track::stops`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Stop {
+    /// The mean position of the points during the stop.
+    pub centre: Location,
+    pub arrival: Timestamp,
+    pub departure: Timestamp,
+    /// Indices of the track points during the stop, which are always consecutive.
+    pub members: Range<usize>,
+}
+
+impl Stop {
+    /// How long the stop lasted.
+    pub fn duration(&self) -> Duration {
+        Duration::from_secs_f64(self.departure.seconds_since(&self.arrival).max(0.))
+    }
+}
+
+impl Track {
+    /// Finds stay points: runs of points that remain within `radius` of the run's first point
+    /// for at least `min_duration`.
+    ///
+    /// Stops don't overlap and are in track order. Collapsing drift or removing outliers
+    /// beforehand helps, since one stray fix can split a stop in two.
+    pub fn stops(&self, radius: &Distance, min_duration: Duration) -> Vec<Stop> {
+        let limit = radius.meters();
+        let minimum = min_duration.as_secs_f64();
+        let points = &self.points;
+        let mut stops = Vec::new();
+
+        let mut i = 0;
+        while i < points.len() {
+            let anchor = &points[i];
+            let end = points[i + 1..]
+                .iter()
+                .position(|point| anchor.location.distance(&point.location).meters() > limit)
+                .map_or(points.len(), |offset| i + 1 + offset);
+
+            let last = &points[end - 1];
+            if last.time.seconds_since(&anchor.time) >= minimum && end - i > 1 {
+                stops.push(Stop {
+                    centre: spherical_mean(points[i..end].iter().map(|point| &point.location))
+                        .unwrap_or_else(|| anchor.location.clone()),
+                    arrival: anchor.time,
+                    departure: last.time,
+                    members: i..end,
+                });
+                i = end;
+            } else {
+                i += 1;
+            }
+        }
+        stops
+    }
+
+    /// Splits the track into the trips between `stops`, as found by `Track::stops`.
+    ///
+    /// Each trip runs from the last point of one stop to the first point of the next, so trips
+    /// and stops share their boundary points. Movement before the first stop and after the last
+    /// is included, and trips with fewer than two points are left out.
+    pub fn trips(&self, stops: &[Stop]) -> Vec<Track> {
+        let mut boundaries = vec![0];
+        for stop in stops {
+            boundaries.push(stop.members.start);
+            boundaries.push(stop.members.end.saturating_sub(1));
+        }
+        boundaries.push(self.points.len().saturating_sub(1));
+
+        boundaries
+            .chunks(2)
+            .filter(|pair| pair[1] > pair[0] && pair[1] < self.points.len())
+            .map(|pair| Track {
+                points: self.points[pair[0]..=pair[1]].to_vec(),
+                moving_threshold: self.moving_threshold,
+            })
+            .collect()
+    }
+}
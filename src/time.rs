@@ -0,0 +1,243 @@
+use core::fmt;
+use core::str::FromStr;
+use libm::round;
+
+/// An error produced while parsing an RFC 3339 / ISO 8601 timestamp.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimestampError {
+    /// The text didn't look like `YYYY-MM-DDTHH:MM:SS[.fff](Z|+HH:MM)`.
+    InvalidFormat { position: usize },
+    /// A field such as the month or the minutes was out of range.
+    OutOfRange { position: usize },
+}
+
+impl fmt::Display for TimestampError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TimestampError::InvalidFormat { position } => {
+                write!(f, "invalid timestamp format at position {}", position)
+            }
+            TimestampError::OutOfRange { position } => {
+                write!(f, "timestamp field out of range at position {}", position)
+            }
+        }
+    }
+}
+
+impl core::error::Error for TimestampError {}
+
+/// A UTC point in time with millisecond precision, stored as milliseconds since the Unix epoch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Timestamp {
+    millis: i64,
+}
+
+const MILLIS_PER_DAY: i64 = 86_400_000;
+
+/// Days since 1970-01-01 for a proleptic Gregorian date (Howard Hinnant's algorithm).
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month = month as i64;
+    let day_of_year =
+        (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + day as i64 - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// The inverse of `days_from_civil`, returning `(year, month, day)`.
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+pub(crate) fn days_in_month(year: i64, month: u32) -> u32 {
+    match month {
+        2 if (year % 4 == 0 && year % 100 != 0) || year % 400 == 0 => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+impl Timestamp {
+    pub const fn from_unix_millis(millis: i64) -> Self {
+        Self { millis }
+    }
+
+    pub fn from_unix_seconds(seconds: f64) -> Self {
+        Self::from_unix_millis(round(seconds * 1000.) as i64)
+    }
+
+    /// Creates a timestamp from a UTC calendar date and time of day.
+    pub fn from_utc(year: i64, month: u32, day: u32, hour: u32, minute: u32, second: f64) -> Self {
+        let days = days_from_civil(year, month, day);
+        let millis = round(second * 1000.) as i64
+            + (hour as i64 * 3600 + minute as i64 * 60) * 1000
+            + days * MILLIS_PER_DAY;
+        Self::from_unix_millis(millis)
+    }
+
+    pub const fn unix_millis(&self) -> i64 {
+        self.millis
+    }
+
+    pub fn unix_seconds(&self) -> f64 {
+        self.millis as f64 / 1000.
+    }
+
+    /// Returns the number of seconds elapsed since `earlier`, negative if `earlier` is later.
+    pub fn seconds_since(&self, earlier: &Timestamp) -> f64 {
+        self.millis.saturating_sub(earlier.millis) as f64 / 1000.
+    }
+
+    /// Returns a timestamp moved forward by the given number of seconds, saturating at the
+    /// earliest and latest representable times.
+    pub fn add_seconds(&self, seconds: f64) -> Self {
+        Self::from_unix_millis(self.millis.saturating_add(round(seconds * 1000.) as i64))
+    }
+
+    /// Splits the timestamp into `(year, month, day, hour, minute, second, millisecond)` in UTC.
+    pub fn to_utc(&self) -> (i64, u32, u32, u32, u32, u32, u32) {
+        let days = self.millis.div_euclid(MILLIS_PER_DAY);
+        let millis_of_day = self.millis.rem_euclid(MILLIS_PER_DAY);
+        let (year, month, day) = civil_from_days(days);
+        let seconds_of_day = (millis_of_day / 1000) as u32;
+        (
+            year,
+            month,
+            day,
+            seconds_of_day / 3600,
+            seconds_of_day / 60 % 60,
+            seconds_of_day % 60,
+            (millis_of_day % 1000) as u32,
+        )
+    }
+
+    /// Parses an RFC 3339 timestamp such as `2024-05-01T12:30:00Z` or
+    /// `2024-05-01T14:30:00.250+02:00`.
+    pub fn parse_rfc3339(input: &str) -> Result<Self, TimestampError> {
+        let bytes = input.as_bytes();
+        let mut position = 0;
+
+        let number = |digits: usize, position: &mut usize| {
+            let start = *position;
+            let field = bytes
+                .get(start..start + digits)
+                .filter(|field| field.iter().all(u8::is_ascii_digit))
+                .ok_or(TimestampError::InvalidFormat { position: start })?;
+            *position += digits;
+            Ok(field
+                .iter()
+                .fold(0u32, |acc, digit| acc * 10 + (digit - b'0') as u32))
+        };
+        let expect = |separators: &[u8], position: &mut usize| match bytes.get(*position) {
+            Some(byte) if separators.contains(byte) => {
+                *position += 1;
+                Ok(())
+            }
+            _ => Err(TimestampError::InvalidFormat {
+                position: *position,
+            }),
+        };
+        let check = |value: u32, range: core::ops::RangeInclusive<u32>, position: usize| {
+            if range.contains(&value) {
+                Ok(value)
+            } else {
+                Err(TimestampError::OutOfRange { position })
+            }
+        };
+
+        let year = number(4, &mut position)? as i64;
+        expect(b"-", &mut position)?;
+        let month = check(number(2, &mut position)?, 1..=12, position - 2)?;
+        expect(b"-", &mut position)?;
+        let day = check(
+            number(2, &mut position)?,
+            1..=days_in_month(year, month),
+            position - 2,
+        )?;
+        expect(b"Tt ", &mut position)?;
+        let hour = check(number(2, &mut position)?, 0..=23, position - 2)?;
+        expect(b":", &mut position)?;
+        let minute = check(number(2, &mut position)?, 0..=59, position - 2)?;
+        expect(b":", &mut position)?;
+        // Allow a leap second, which is folded into the following second
+        let second = check(number(2, &mut position)?, 0..=60, position - 2)?;
+
+        let mut millis = 0.;
+        if bytes.get(position) == Some(&b'.') {
+            position += 1;
+            let start = position;
+            let mut scale = 100.;
+            while let Some(digit) = bytes.get(position).filter(|byte| byte.is_ascii_digit()) {
+                millis += (digit - b'0') as f64 * scale;
+                scale /= 10.;
+                position += 1;
+            }
+            if position == start {
+                return Err(TimestampError::InvalidFormat { position });
+            }
+        }
+
+        let offset_minutes = match bytes.get(position) {
+            Some(b'Z') | Some(b'z') => {
+                position += 1;
+                0
+            }
+            Some(sign @ (b'+' | b'-')) => {
+                let sign = if *sign == b'-' { -1 } else { 1 };
+                position += 1;
+                let hours = check(number(2, &mut position)?, 0..=23, position - 2)?;
+                expect(b":", &mut position)?;
+                let minutes = check(number(2, &mut position)?, 0..=59, position - 2)?;
+                sign * (hours * 60 + minutes) as i64
+            }
+            _ => return Err(TimestampError::InvalidFormat { position }),
+        };
+
+        if position != bytes.len() {
+            return Err(TimestampError::InvalidFormat { position });
+        }
+
+        let utc = Self::from_utc(year, month, day, hour, minute, second as f64);
+        Ok(Self::from_unix_millis(
+            utc.millis + round(millis) as i64 - offset_minutes * 60_000,
+        ))
+    }
+}
+
+/// Formats the timestamp as RFC 3339 in UTC, e.g. `2024-05-01T12:30:00Z`.
+/// Milliseconds are only written when they are not zero.
+impl fmt::Display for Timestamp {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (year, month, day, hour, minute, second, millis) = self.to_utc();
+        write!(
+            f,
+            "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}",
+            year, month, day, hour, minute, second
+        )?;
+        if millis != 0 {
+            write!(f, ".{:03}", millis)?;
+        }
+        write!(f, "Z")
+    }
+}
+
+impl FromStr for Timestamp {
+    type Err = TimestampError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        Self::parse_rfc3339(input)
+    }
+}
@@ -0,0 +1,235 @@
+use core::time::Duration;
+
+use crate::gpx::GpxTrackSegment;
+use crate::location::Location;
+use crate::measurement::Distance;
+use crate::speed::Speed;
+use crate::time::Timestamp;
+
+/// A location recorded at a point in time, like a GPS fix.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrackPoint {
+    pub location: Location,
+    pub time: Timestamp,
+    /// Elevation in meters.
+    pub elevation: Option<f64>,
+}
+
+impl TrackPoint {
+    pub fn from(location: Location, time: Timestamp) -> Self {
+        Self {
+            location,
+            time,
+            elevation: None,
+        }
+    }
+}
+
+/// A number of seconds as a `Duration`. Negative and NaN values become zero, and values too
+/// large for a `Duration` saturate.
+fn from_seconds(seconds: f64) -> Duration {
+    Duration::try_from_secs_f64(seconds.max(0.)).unwrap_or(Duration::MAX)
+}
+
+/// The time between two timestamps, or zero if they're out of order.
+fn between(earlier: &Timestamp, later: &Timestamp) -> Duration {
+    from_seconds(later.seconds_since(earlier))
+}
+
+/// One split from `Track::splits`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Split {
+    /// The split's length. Every split but the last is the requested distance.
+    pub distance: Distance,
+    pub duration: Duration,
+}
+
+impl Split {
+    /// The average speed over the split, or `None` if no time passed.
+    pub fn speed(&self) -> Option<Speed> {
+        (!self.duration.is_zero()).then(|| self.distance / self.duration)
+    }
+
+    /// The time taken per `per` of distance, like minutes per kilometer for running pace.
+    pub fn pace(&self, per: Distance) -> Option<Duration> {
+        self.speed()?.time_for(per)
+    }
+}
+
+/// Timestamped points in the order they were recorded, like a run or a vehicle trip.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Track {
+    pub points: Vec<TrackPoint>,
+    /// Segments slower than this count as stopped for the moving distance and time. Defaults to 0.5 m/s.
+    pub moving_threshold: Speed,
+}
+
+impl Default for Track {
+    fn default() -> Self {
+        Self::from(Vec::new())
+    }
+}
+
+impl Track {
+    pub fn from(points: Vec<TrackPoint>) -> Self {
+        Self {
+            points,
+            moving_threshold: Speed::from_meters_per_second(0.5),
+        }
+    }
+
+    /// Each pair of consecutive points with the distance and time between them.
+    fn segments(&self) -> impl Iterator<Item = (Distance, Duration)> + '_ {
+        self.points.windows(2).map(|pair| {
+            let (a, b) = (&pair[0], &pair[1]);
+            (a.location.distance(&b.location), between(&a.time, &b.time))
+        })
+    }
+
+    fn is_moving(&self, distance: Distance, duration: Duration) -> bool {
+        !duration.is_zero() && distance / duration >= self.moving_threshold
+    }
+
+    /// The length of the track, following every point.
+    pub fn distance(&self) -> Distance {
+        self.segments().map(|(distance, _)| distance).sum()
+    }
+
+    /// The distance covered while moving faster than `moving_threshold`, which ignores GPS drift while stopped.
+    pub fn moving_distance(&self) -> Distance {
+        self.segments()
+            .filter(|&(distance, duration)| self.is_moving(distance, duration))
+            .map(|(distance, _)| distance)
+            .sum()
+    }
+
+    /// The time from the first point to the last.
+    pub fn elapsed_time(&self) -> Duration {
+        match (self.points.first(), self.points.last()) {
+            (Some(first), Some(last)) => between(&first.time, &last.time),
+            _ => Duration::ZERO,
+        }
+    }
+
+    /// The time spent moving faster than `moving_threshold`.
+    pub fn moving_time(&self) -> Duration {
+        self.segments()
+            .filter(|&(distance, duration)| self.is_moving(distance, duration))
+            .map(|(_, duration)| duration)
+            .sum()
+    }
+
+    /// The distance over the elapsed time, or `None` if no time passed.
+    pub fn average_speed(&self) -> Option<Speed> {
+        let elapsed = self.elapsed_time();
+        (!elapsed.is_zero()).then(|| self.distance() / elapsed)
+    }
+
+    /// The moving distance over the moving time, or `None` if the track never moved.
+    pub fn average_moving_speed(&self) -> Option<Speed> {
+        let moving = self.moving_time();
+        (!moving.is_zero()).then(|| self.moving_distance() / moving)
+    }
+
+    /// The speed between each pair of consecutive points, so there's one fewer than the points.
+    /// `None` where no time passed between them.
+    pub fn segment_speeds(&self) -> Vec<Option<Speed>> {
+        self.segments()
+            .map(|(distance, duration)| (!duration.is_zero()).then(|| distance / duration))
+            .collect()
+    }
+
+    /// The fastest segment speed. A single bad fix can make this spike, so clean noisy tracks first.
+    pub fn max_speed(&self) -> Option<Speed> {
+        self.segment_speeds()
+            .into_iter()
+            .flatten()
+            .max_by(|a, b| a.meters_per_second().total_cmp(&b.meters_per_second()))
+    }
+
+    /// The total climb and descent, in that order, between points that have an elevation.
+    fn elevation_changes(&self) -> (f64, f64) {
+        let mut elevations = self.points.iter().filter_map(|point| point.elevation);
+        let mut previous = match elevations.next() {
+            Some(elevation) => elevation,
+            None => return (0., 0.),
+        };
+        let (mut gain, mut loss) = (0., 0.);
+        for elevation in elevations {
+            let change = elevation - previous;
+            if change > 0. {
+                gain += change;
+            } else {
+                loss -= change;
+            }
+            previous = elevation;
+        }
+        (gain, loss)
+    }
+
+    /// The total climb between points that have an elevation.
+    pub fn elevation_gain(&self) -> Distance {
+        Distance::from_meters(self.elevation_changes().0)
+    }
+
+    /// The total descent between points that have an elevation, as a positive distance.
+    pub fn elevation_loss(&self) -> Distance {
+        Distance::from_meters(self.elevation_changes().1)
+    }
+
+    /// Cuts the track every `every` of distance, like per kilometer splits for a run, and times each one.
+    ///
+    /// Times at the boundaries are interpolated along the segment they fall in. The last split
+    /// holds whatever distance remains, and is left out if there's none.
+    pub fn splits(&self, every: &Distance) -> Vec<Split> {
+        let length = every.meters();
+        let mut splits = Vec::new();
+        if length <= 0. || !length.is_finite() {
+            return splits;
+        }
+
+        let (mut covered, mut elapsed) = (0., 0.);
+        for (distance, duration) in self.segments() {
+            let (mut distance, mut duration) = (distance.meters(), duration.as_secs_f64());
+            while covered + distance >= length {
+                let fraction = (length - covered) / distance;
+                elapsed += duration * fraction;
+                splits.push(Split {
+                    distance: Distance::from_meters(length),
+                    duration: from_seconds(elapsed),
+                });
+                distance -= length - covered;
+                duration -= duration * fraction;
+                covered = 0.;
+                elapsed = 0.;
+            }
+            covered += distance;
+            elapsed += duration;
+        }
+        if covered > 0. {
+            splits.push(Split {
+                distance: Distance::from_meters(covered),
+                duration: from_seconds(elapsed),
+            });
+        }
+        splits
+    }
+}
+
+impl GpxTrackSegment {
+    /// The segment as a `Track`, leaving out points without a time.
+    pub fn to_track(&self) -> Track {
+        Track::from(
+            self.points
+                .iter()
+                .filter_map(|point| {
+                    Some(TrackPoint {
+                        location: point.location.clone(),
+                        time: point.time?,
+                        elevation: point.elevation,
+                    })
+                })
+                .collect(),
+        )
+    }
+}
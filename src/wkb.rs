@@ -0,0 +1,412 @@
+use core::fmt;
+
+use crate::geometry::{Geometry, LineString, Polygon};
+use crate::location::Location;
+
+const EWKB_Z: u32 = 0x8000_0000;
+const EWKB_M: u32 = 0x4000_0000;
+const EWKB_SRID: u32 = 0x2000_0000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ByteOrder {
+    BigEndian,
+    LittleEndian,
+}
+
+/// An error produced while reading Well-Known Binary. Offsets are byte offsets into the input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WkbError {
+    /// The input ended in the middle of a geometry.
+    UnexpectedEnd { offset: usize },
+    /// The byte order marker wasn't `0` or `1`.
+    InvalidByteOrder { byte: u8, offset: usize },
+    /// The geometry type code isn't one of the supported types.
+    UnknownGeometryType { code: u32, offset: usize },
+    /// A multi geometry contained a member of the wrong type.
+    UnexpectedMember { code: u32, offset: usize },
+    /// A point with NaN coordinates (`POINT EMPTY`) has no location to return.
+    EmptyPoint { offset: usize },
+    /// Bytes were left over after the geometry.
+    TrailingBytes { offset: usize },
+    /// A hex encoded string contained an invalid digit or had an odd length.
+    InvalidHex { position: usize },
+}
+
+impl fmt::Display for WkbError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            WkbError::UnexpectedEnd { offset } => {
+                write!(f, "unexpected end of WKB at offset {}", offset)
+            }
+            WkbError::InvalidByteOrder { byte, offset } => {
+                write!(f, "invalid byte order {} at offset {}", byte, offset)
+            }
+            WkbError::UnknownGeometryType { code, offset } => {
+                write!(f, "unknown geometry type {} at offset {}", code, offset)
+            }
+            WkbError::UnexpectedMember { code, offset } => {
+                write!(f, "unexpected member type {} at offset {}", code, offset)
+            }
+            WkbError::EmptyPoint { offset } => {
+                write!(f, "empty point at offset {} has no location", offset)
+            }
+            WkbError::TrailingBytes { offset } => {
+                write!(f, "unexpected trailing bytes at offset {}", offset)
+            }
+            WkbError::InvalidHex { position } => {
+                write!(f, "invalid hex at position {}", position)
+            }
+        }
+    }
+}
+
+impl std::error::Error for WkbError {}
+
+fn type_code(geometry: &Geometry) -> u32 {
+    match geometry {
+        Geometry::Point(_) => 1,
+        Geometry::LineString(_) => 2,
+        Geometry::Polygon(_) => 3,
+        Geometry::MultiPoint(_) => 4,
+        Geometry::MultiLineString(_) => 5,
+        Geometry::MultiPolygon(_) => 6,
+    }
+}
+
+struct Writer {
+    order: ByteOrder,
+    bytes: Vec<u8>,
+}
+
+impl Writer {
+    fn u32(&mut self, value: u32) {
+        match self.order {
+            ByteOrder::BigEndian => self.bytes.extend_from_slice(&value.to_be_bytes()),
+            ByteOrder::LittleEndian => self.bytes.extend_from_slice(&value.to_le_bytes()),
+        }
+    }
+
+    fn f64(&mut self, value: f64) {
+        match self.order {
+            ByteOrder::BigEndian => self.bytes.extend_from_slice(&value.to_be_bytes()),
+            ByteOrder::LittleEndian => self.bytes.extend_from_slice(&value.to_le_bytes()),
+        }
+    }
+
+    fn header(&mut self, code: u32, srid: Option<u32>) {
+        self.bytes.push(match self.order {
+            ByteOrder::BigEndian => 0,
+            ByteOrder::LittleEndian => 1,
+        });
+        match srid {
+            Some(srid) => {
+                self.u32(code | EWKB_SRID);
+                self.u32(srid);
+            }
+            None => self.u32(code),
+        }
+    }
+
+    fn coordinate(&mut self, location: &Location) {
+        self.f64(location.longitude);
+        self.f64(location.latitude);
+    }
+
+    fn points(&mut self, points: &[Location]) {
+        self.u32(points.len() as u32);
+        points.iter().for_each(|point| self.coordinate(point));
+    }
+
+    fn polygon(&mut self, polygon: &Polygon) {
+        if polygon.exterior.points.is_empty() {
+            self.u32(0);
+            return;
+        }
+        self.u32(1 + polygon.interiors.len() as u32);
+        for ring in polygon.rings() {
+            self.points(&ring.closed_points());
+        }
+    }
+
+    fn geometry(&mut self, geometry: &Geometry, srid: Option<u32>) {
+        self.header(type_code(geometry), srid);
+        match geometry {
+            Geometry::Point(location) => self.coordinate(location),
+            Geometry::LineString(line) => self.points(&line.points),
+            Geometry::Polygon(polygon) => self.polygon(polygon),
+            Geometry::MultiPoint(points) => {
+                self.u32(points.len() as u32);
+                for point in points {
+                    self.header(1, None);
+                    self.coordinate(point);
+                }
+            }
+            Geometry::MultiLineString(lines) => {
+                self.u32(lines.len() as u32);
+                for line in lines {
+                    self.header(2, None);
+                    self.points(&line.points);
+                }
+            }
+            Geometry::MultiPolygon(polygons) => {
+                self.u32(polygons.len() as u32);
+                for polygon in polygons {
+                    self.header(3, None);
+                    self.polygon(polygon);
+                }
+            }
+        }
+    }
+}
+
+struct Header {
+    order: ByteOrder,
+    code: u32,
+    dimensions: usize,
+    srid: Option<u32>,
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take<const N: usize>(&mut self) -> Result<[u8; N], WkbError> {
+        let bytes =
+            self.bytes
+                .get(self.offset..self.offset + N)
+                .ok_or(WkbError::UnexpectedEnd {
+                    offset: self.bytes.len(),
+                })?;
+        self.offset += N;
+        let mut array = [0; N];
+        array.copy_from_slice(bytes);
+        Ok(array)
+    }
+
+    fn u32(&mut self, order: ByteOrder) -> Result<u32, WkbError> {
+        let bytes = self.take()?;
+        Ok(match order {
+            ByteOrder::BigEndian => u32::from_be_bytes(bytes),
+            ByteOrder::LittleEndian => u32::from_le_bytes(bytes),
+        })
+    }
+
+    fn f64(&mut self, order: ByteOrder) -> Result<f64, WkbError> {
+        let bytes = self.take()?;
+        Ok(match order {
+            ByteOrder::BigEndian => f64::from_be_bytes(bytes),
+            ByteOrder::LittleEndian => f64::from_le_bytes(bytes),
+        })
+    }
+
+    /// Reads the byte order, type code and optional SRID. Both EWKB flags
+    /// and ISO style (1000/2000/3000 offset) dimension codes are understood.
+    fn header(&mut self) -> Result<Header, WkbError> {
+        let offset = self.offset;
+        let order = match self.take::<1>()?[0] {
+            0 => ByteOrder::BigEndian,
+            1 => ByteOrder::LittleEndian,
+            byte => return Err(WkbError::InvalidByteOrder { byte, offset }),
+        };
+
+        let raw = self.u32(order)?;
+        let srid = if raw & EWKB_SRID != 0 {
+            Some(self.u32(order)?)
+        } else {
+            None
+        };
+
+        let mut dimensions = 2;
+        if raw & EWKB_Z != 0 {
+            dimensions += 1;
+        }
+        if raw & EWKB_M != 0 {
+            dimensions += 1;
+        }
+
+        let iso = raw & 0x0fff_ffff;
+        let code = iso % 1000;
+        dimensions += match iso / 1000 {
+            1 | 2 => 1,
+            3 => 2,
+            _ => 0,
+        };
+
+        if !(1..=6).contains(&code) {
+            return Err(WkbError::UnknownGeometryType { code: raw, offset });
+        }
+
+        Ok(Header {
+            order,
+            code,
+            dimensions,
+            srid,
+        })
+    }
+
+    fn coordinate(&mut self, header: &Header) -> Result<Location, WkbError> {
+        let longitude = self.f64(header.order)?;
+        let latitude = self.f64(header.order)?;
+        for _ in 2..header.dimensions {
+            self.f64(header.order)?;
+        }
+        Ok(Location::from(latitude, longitude))
+    }
+
+    fn count(&mut self, header: &Header) -> Result<usize, WkbError> {
+        let count = self.u32(header.order)? as usize;
+        // Every member takes at least 4 bytes, so larger counts can't be valid
+        if count > self.bytes.len().saturating_sub(self.offset) / 4 + 1 {
+            return Err(WkbError::UnexpectedEnd {
+                offset: self.bytes.len(),
+            });
+        }
+        Ok(count)
+    }
+
+    fn line_string(&mut self, header: &Header) -> Result<LineString, WkbError> {
+        let count = self.count(header)?;
+        let points = (0..count)
+            .map(|_| self.coordinate(header))
+            .collect::<Result<_, _>>()?;
+        Ok(LineString::from(points))
+    }
+
+    fn polygon(&mut self, header: &Header) -> Result<Polygon, WkbError> {
+        let count = self.count(header)?;
+        let mut rings = (0..count)
+            .map(|_| self.line_string(header))
+            .collect::<Result<Vec<_>, _>>()?
+            .into_iter();
+        let exterior = rings.next().unwrap_or_default();
+        Ok(Polygon::from(exterior, rings.collect()))
+    }
+
+    /// Reads the members of a multi geometry, checking each member's type before reading its body.
+    fn members<T>(
+        &mut self,
+        header: &Header,
+        code: u32,
+        extract: fn(Geometry) -> Option<T>,
+    ) -> Result<Vec<T>, WkbError> {
+        let count = self.count(header)?;
+        let mut members = Vec::with_capacity(count);
+        for _ in 0..count {
+            let offset = self.offset;
+            let member = self.header()?;
+            if member.code != code {
+                return Err(WkbError::UnexpectedMember {
+                    code: member.code,
+                    offset,
+                });
+            }
+            let geometry = self.body(&member, offset)?;
+            members.extend(extract(geometry));
+        }
+        Ok(members)
+    }
+
+    fn geometry(&mut self) -> Result<(Geometry, Option<u32>), WkbError> {
+        let offset = self.offset;
+        let header = self.header()?;
+        let geometry = self.body(&header, offset)?;
+        Ok((geometry, header.srid))
+    }
+
+    /// Reads the rest of a geometry after its header, which started at `offset`. Members of
+    /// multi geometries are checked to be single geometries first, so this recurses at most once.
+    fn body(&mut self, header: &Header, offset: usize) -> Result<Geometry, WkbError> {
+        let geometry = match header.code {
+            1 => {
+                let location = self.coordinate(header)?;
+                if location.latitude.is_nan() && location.longitude.is_nan() {
+                    return Err(WkbError::EmptyPoint { offset });
+                }
+                Geometry::Point(location)
+            }
+            2 => Geometry::LineString(self.line_string(header)?),
+            3 => Geometry::Polygon(self.polygon(header)?),
+            4 => Geometry::MultiPoint(self.members(header, 1, |member| match member {
+                Geometry::Point(point) => Some(point),
+                _ => None,
+            })?),
+            5 => Geometry::MultiLineString(self.members(header, 2, |member| match member {
+                Geometry::LineString(line) => Some(line),
+                _ => None,
+            })?),
+            _ => Geometry::MultiPolygon(self.members(header, 3, |member| match member {
+                Geometry::Polygon(polygon) => Some(polygon),
+                _ => None,
+            })?),
+        };
+        Ok(geometry)
+    }
+}
+
+fn decode_hex(input: &str) -> Result<Vec<u8>, WkbError> {
+    let input = input.trim();
+    if !input.len().is_multiple_of(2) {
+        return Err(WkbError::InvalidHex {
+            position: input.len(),
+        });
+    }
+    (0..input.len())
+        .step_by(2)
+        .map(|i| {
+            input
+                .get(i..i + 2)
+                .filter(|pair| pair.bytes().all(|b| b.is_ascii_hexdigit()))
+                .and_then(|pair| u8::from_str_radix(pair, 16).ok())
+                .ok_or(WkbError::InvalidHex { position: i })
+        })
+        .collect()
+}
+
+impl Geometry {
+    /// Encodes the geometry as OGC Well-Known Binary.
+    pub fn to_wkb(&self, order: ByteOrder) -> Vec<u8> {
+        self.to_ewkb(order, None)
+    }
+
+    /// Encodes the geometry as PostGIS extended WKB, embedding the SRID if one is given.
+    pub fn to_ewkb(&self, order: ByteOrder, srid: Option<u32>) -> Vec<u8> {
+        let mut writer = Writer {
+            order,
+            bytes: Vec::new(),
+        };
+        writer.geometry(self, srid);
+        writer.bytes
+    }
+
+    /// Encodes the geometry as uppercase hex EWKB, the format PostGIS prints geometries in.
+    pub fn to_ewkb_hex(&self, order: ByteOrder, srid: Option<u32>) -> String {
+        self.to_ewkb(order, srid)
+            .iter()
+            .map(|byte| format!("{:02X}", byte))
+            .collect()
+    }
+
+    /// Decodes WKB or EWKB in either byte order. Any SRID is ignored.
+    pub fn from_wkb(bytes: &[u8]) -> Result<Self, WkbError> {
+        Self::from_ewkb(bytes).map(|(geometry, _)| geometry)
+    }
+
+    /// Decodes WKB or EWKB in either byte order, returning the SRID if one was embedded.
+    pub fn from_ewkb(bytes: &[u8]) -> Result<(Self, Option<u32>), WkbError> {
+        let mut reader = Reader { bytes, offset: 0 };
+        let result = reader.geometry()?;
+        if reader.offset != bytes.len() {
+            return Err(WkbError::TrailingBytes {
+                offset: reader.offset,
+            });
+        }
+        Ok(result)
+    }
+
+    /// Decodes hex encoded WKB or EWKB, returning the SRID if one was embedded.
+    pub fn from_ewkb_hex(input: &str) -> Result<(Self, Option<u32>), WkbError> {
+        Self::from_ewkb(&decode_hex(input)?)
+    }
+}
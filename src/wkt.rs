@@ -0,0 +1,444 @@
+use core::fmt;
+use core::str::FromStr;
+
+use crate::geometry::{Geometry, LineString, Polygon};
+use crate::location::Location;
+
+/// An error produced while parsing Well-Known Text. Positions are byte offsets into the input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WktError {
+    /// The input ended while `expected` was still needed.
+    UnexpectedEnd { expected: &'static str },
+    /// Something other than `expected` was found.
+    UnexpectedToken {
+        expected: &'static str,
+        position: usize,
+    },
+    /// The geometry keyword isn't one of the supported types.
+    UnknownGeometry { name: String, position: usize },
+    /// A coordinate couldn't be parsed as a number.
+    InvalidNumber { position: usize },
+    /// `POINT EMPTY` has no location to return.
+    EmptyPoint { position: usize },
+}
+
+impl fmt::Display for WktError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            WktError::UnexpectedEnd { expected } => {
+                write!(f, "unexpected end of WKT, expected {}", expected)
+            }
+            WktError::UnexpectedToken { expected, position } => {
+                write!(f, "expected {} at position {}", expected, position)
+            }
+            WktError::UnknownGeometry { name, position } => {
+                write!(
+                    f,
+                    "unknown geometry type {:?} at position {}",
+                    name, position
+                )
+            }
+            WktError::InvalidNumber { position } => {
+                write!(f, "invalid number at position {}", position)
+            }
+            WktError::EmptyPoint { position } => {
+                write!(f, "empty point at position {} has no location", position)
+            }
+        }
+    }
+}
+
+impl std::error::Error for WktError {}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Token<'a> {
+    Word(&'a str),
+    Number(&'a str),
+    Open,
+    Close,
+    Comma,
+    Semicolon,
+    Equals,
+}
+
+struct Tokenizer<'a> {
+    input: &'a str,
+    position: usize,
+}
+
+impl<'a> Tokenizer<'a> {
+    fn new(input: &'a str) -> Self {
+        Self { input, position: 0 }
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = &self.input[self.position..];
+        self.position += rest.len() - rest.trim_start().len();
+    }
+
+    fn peek(&mut self) -> Option<(Token<'a>, usize)> {
+        self.skip_whitespace();
+        let start = self.position;
+        let rest = &self.input[start..];
+        let first = rest.chars().next()?;
+
+        let token = match first {
+            '(' => Token::Open,
+            ')' => Token::Close,
+            ',' => Token::Comma,
+            ';' => Token::Semicolon,
+            '=' => Token::Equals,
+            c if c.is_ascii_alphabetic() => {
+                let len = rest
+                    .find(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+                    .unwrap_or(rest.len());
+                Token::Word(&rest[..len])
+            }
+            _ => {
+                let len = rest
+                    .find(|c: char| {
+                        !(c.is_ascii_digit() || matches!(c, '-' | '+' | '.' | 'e' | 'E'))
+                    })
+                    .unwrap_or(rest.len());
+                Token::Number(&rest[..len.max(first.len_utf8())])
+            }
+        };
+
+        Some((token, start))
+    }
+
+    fn next(&mut self) -> Option<(Token<'a>, usize)> {
+        let (token, start) = self.peek()?;
+        self.position = start
+            + match token {
+                Token::Word(word) | Token::Number(word) => word.len(),
+                _ => 1,
+            };
+        Some((token, start))
+    }
+
+    fn expect(&mut self, expected: Token, name: &'static str) -> Result<(), WktError> {
+        match self.next() {
+            Some((token, _)) if token == expected => Ok(()),
+            Some((_, position)) => Err(WktError::UnexpectedToken {
+                expected: name,
+                position,
+            }),
+            None => Err(WktError::UnexpectedEnd { expected: name }),
+        }
+    }
+
+    fn number(&mut self) -> Result<f64, WktError> {
+        match self.next() {
+            Some((Token::Number(text), position)) => text
+                .parse()
+                .map_err(|_| WktError::InvalidNumber { position }),
+            Some((_, position)) => Err(WktError::UnexpectedToken {
+                expected: "number",
+                position,
+            }),
+            None => Err(WktError::UnexpectedEnd { expected: "number" }),
+        }
+    }
+
+    /// Consumes a `Z`, `M` or `ZM` dimension marker and returns true if an
+    /// `EMPTY` keyword follows.
+    fn dimensions_and_empty(&mut self) -> bool {
+        if let Some((Token::Word(word), _)) = self.peek() {
+            if ["Z", "M", "ZM"]
+                .iter()
+                .any(|d| word.eq_ignore_ascii_case(d))
+            {
+                self.next();
+            }
+        }
+        self.empty()
+    }
+
+    /// Consumes an `EMPTY` keyword if one is next.
+    fn empty(&mut self) -> bool {
+        match self.peek() {
+            Some((Token::Word(word), _)) if word.eq_ignore_ascii_case("EMPTY") => {
+                self.next();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Parses `x y [z [m]]`, ignoring any ordinates past the second.
+    fn coordinate(&mut self) -> Result<Location, WktError> {
+        let longitude = self.number()?;
+        let latitude = self.number()?;
+        while let Some((Token::Number(_), _)) = self.peek() {
+            self.number()?;
+        }
+        Ok(Location::from(latitude, longitude))
+    }
+
+    /// Parses a parenthesized, comma separated list.
+    fn list<T>(
+        &mut self,
+        mut item: impl FnMut(&mut Self) -> Result<T, WktError>,
+    ) -> Result<Vec<T>, WktError> {
+        self.expect(Token::Open, "'('")?;
+        let mut items = vec![item(self)?];
+        loop {
+            match self.next() {
+                Some((Token::Comma, _)) => items.push(item(self)?),
+                Some((Token::Close, _)) => return Ok(items),
+                Some((_, position)) => {
+                    return Err(WktError::UnexpectedToken {
+                        expected: "',' or ')'",
+                        position,
+                    })
+                }
+                None => {
+                    return Err(WktError::UnexpectedEnd {
+                        expected: "',' or ')'",
+                    })
+                }
+            }
+        }
+    }
+
+    /// Parses a line string's coordinates, or `EMPTY` as a member of a multi line string.
+    fn line_string(&mut self) -> Result<LineString, WktError> {
+        if self.empty() {
+            return Ok(LineString::default());
+        }
+        Ok(LineString::from(self.list(Self::coordinate)?))
+    }
+
+    /// Parses a polygon's rings, or `EMPTY` as a member of a multi polygon.
+    fn polygon(&mut self) -> Result<Polygon, WktError> {
+        if self.empty() {
+            return Ok(Polygon::default());
+        }
+        let mut rings = self.list(Self::line_string)?.into_iter();
+        let exterior = rings.next().unwrap_or_default();
+        Ok(Polygon::from(exterior, rings.collect()))
+    }
+
+    /// A multipoint member may or may not be wrapped in its own parentheses.
+    fn multi_point_member(&mut self) -> Result<Location, WktError> {
+        if let Some((Token::Open, _)) = self.peek() {
+            self.next();
+            let location = self.coordinate()?;
+            self.expect(Token::Close, "')'")?;
+            Ok(location)
+        } else {
+            self.coordinate()
+        }
+    }
+
+    fn geometry(&mut self) -> Result<Geometry, WktError> {
+        let (name, position) = match self.next() {
+            Some((Token::Word(name), position)) => (name, position),
+            Some((_, position)) => {
+                return Err(WktError::UnexpectedToken {
+                    expected: "geometry type",
+                    position,
+                })
+            }
+            None => {
+                return Err(WktError::UnexpectedEnd {
+                    expected: "geometry type",
+                })
+            }
+        };
+        let name = name.to_ascii_uppercase();
+        let empty = self.dimensions_and_empty();
+
+        let geometry = match name.as_str() {
+            "POINT" if empty => return Err(WktError::EmptyPoint { position }),
+            "POINT" => {
+                self.expect(Token::Open, "'('")?;
+                let location = self.coordinate()?;
+                self.expect(Token::Close, "')'")?;
+                Geometry::Point(location)
+            }
+            "LINESTRING" if empty => Geometry::LineString(LineString::default()),
+            "LINESTRING" => Geometry::LineString(self.line_string()?),
+            "POLYGON" if empty => Geometry::Polygon(Polygon::default()),
+            "POLYGON" => Geometry::Polygon(self.polygon()?),
+            "MULTIPOINT" if empty => Geometry::MultiPoint(vec![]),
+            "MULTIPOINT" => Geometry::MultiPoint(self.list(Self::multi_point_member)?),
+            "MULTILINESTRING" if empty => Geometry::MultiLineString(vec![]),
+            "MULTILINESTRING" => Geometry::MultiLineString(self.list(Self::line_string)?),
+            "MULTIPOLYGON" if empty => Geometry::MultiPolygon(vec![]),
+            "MULTIPOLYGON" => Geometry::MultiPolygon(self.list(Self::polygon)?),
+            _ => return Err(WktError::UnknownGeometry { name, position }),
+        };
+
+        Ok(geometry)
+    }
+
+    /// Parses an optional `SRID=4326;` prefix.
+    fn srid(&mut self) -> Result<Option<u32>, WktError> {
+        match self.peek() {
+            Some((Token::Word(word), _)) if word.eq_ignore_ascii_case("SRID") => {
+                self.next();
+                self.expect(Token::Equals, "'='")?;
+                let srid = match self.next() {
+                    Some((Token::Number(text), position)) => text
+                        .parse()
+                        .map_err(|_| WktError::InvalidNumber { position })?,
+                    Some((_, position)) => {
+                        return Err(WktError::UnexpectedToken {
+                            expected: "SRID",
+                            position,
+                        })
+                    }
+                    None => return Err(WktError::UnexpectedEnd { expected: "SRID" }),
+                };
+                self.expect(Token::Semicolon, "';'")?;
+                Ok(Some(srid))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    fn finish(&mut self) -> Result<(), WktError> {
+        match self.peek() {
+            Some((_, position)) => Err(WktError::UnexpectedToken {
+                expected: "end of input",
+                position,
+            }),
+            None => Ok(()),
+        }
+    }
+}
+
+fn write_coordinate(f: &mut fmt::Formatter, location: &Location) -> fmt::Result {
+    write!(f, "{} {}", location.longitude, location.latitude)
+}
+
+/// The space between a geometry keyword and `EMPTY`, which members of a list don't have.
+fn separator(empty: bool) -> &'static str {
+    if empty {
+        " "
+    } else {
+        ""
+    }
+}
+
+fn write_list<T>(
+    f: &mut fmt::Formatter,
+    items: &[T],
+    mut write_item: impl FnMut(&mut fmt::Formatter, &T) -> fmt::Result,
+) -> fmt::Result {
+    if items.is_empty() {
+        return write!(f, "EMPTY");
+    }
+    write!(f, "(")?;
+    for (i, item) in items.iter().enumerate() {
+        if i > 0 {
+            write!(f, ",")?;
+        }
+        write_item(f, item)?;
+    }
+    write!(f, ")")
+}
+
+fn write_polygon(f: &mut fmt::Formatter, polygon: &Polygon) -> fmt::Result {
+    if polygon.exterior.points.is_empty() {
+        return write!(f, "EMPTY");
+    }
+    let rings: Vec<_> = polygon.rings().map(LineString::closed_points).collect();
+    write_list(f, &rings, |f, ring| write_list(f, ring, write_coordinate))
+}
+
+/// Formats the geometry as Well-Known Text, e.g. `POINT(-111.76 40.78)`.
+impl fmt::Display for Geometry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Geometry::Point(location) => {
+                write!(f, "POINT(")?;
+                write_coordinate(f, location)?;
+                write!(f, ")")
+            }
+            Geometry::MultiPoint(locations) => {
+                write!(f, "MULTIPOINT{}", separator(locations.is_empty()))?;
+                write_list(f, locations, |f, location| {
+                    write!(f, "(")?;
+                    write_coordinate(f, location)?;
+                    write!(f, ")")
+                })
+            }
+            Geometry::LineString(line) => {
+                write!(f, "LINESTRING{}", separator(line.points.is_empty()))?;
+                write_list(f, &line.points, write_coordinate)
+            }
+            Geometry::MultiLineString(lines) => {
+                write!(f, "MULTILINESTRING{}", separator(lines.is_empty()))?;
+                write_list(f, lines, |f, line| {
+                    write_list(f, &line.points, write_coordinate)
+                })
+            }
+            Geometry::Polygon(polygon) => {
+                write!(
+                    f,
+                    "POLYGON{}",
+                    separator(polygon.exterior.points.is_empty())
+                )?;
+                write_polygon(f, polygon)
+            }
+            Geometry::MultiPolygon(polygons) => {
+                write!(f, "MULTIPOLYGON{}", separator(polygons.is_empty()))?;
+                write_list(f, polygons, write_polygon)
+            }
+        }
+    }
+}
+
+impl Geometry {
+    pub fn to_wkt(&self) -> String {
+        self.to_string()
+    }
+
+    /// Formats the geometry as extended WKT with an SRID prefix, e.g. `SRID=4326;POINT(...)`.
+    pub fn to_ewkt(&self, srid: u32) -> String {
+        format!("SRID={};{}", srid, self)
+    }
+
+    /// Parses Well-Known Text. An EWKT `SRID=...;` prefix is accepted and ignored.
+    pub fn from_wkt(input: &str) -> Result<Self, WktError> {
+        Self::from_ewkt(input).map(|(geometry, _)| geometry)
+    }
+
+    /// Parses extended Well-Known Text, returning the SRID if one was given.
+    pub fn from_ewkt(input: &str) -> Result<(Self, Option<u32>), WktError> {
+        let mut tokenizer = Tokenizer::new(input);
+        let srid = tokenizer.srid()?;
+        let geometry = tokenizer.geometry()?;
+        tokenizer.finish()?;
+        Ok((geometry, srid))
+    }
+}
+
+impl FromStr for Geometry {
+    type Err = WktError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        Self::from_wkt(input)
+    }
+}
+
+impl Location {
+    /// Formats the location as a WKT point, e.g. `POINT(-111.76 40.78)`.
+    pub fn to_wkt(&self) -> String {
+        Geometry::Point(self.clone()).to_wkt()
+    }
+
+    /// Parses a WKT point.
+    pub fn from_wkt(input: &str) -> Result<Self, WktError> {
+        match Geometry::from_wkt(input)? {
+            Geometry::Point(location) => Ok(location),
+            _ => Err(WktError::UnexpectedToken {
+                expected: "POINT",
+                position: input.len() - input.trim_start().len(),
+            }),
+        }
+    }
+}
@@ -0,0 +1,382 @@
+use core::fmt;
+
+/// How deeply elements may nest. GPX and KML documents stay well under this, and the limit keeps
+/// hostile documents from building trees too deep to drop or walk.
+const MAX_DEPTH: usize = 256;
+
+/// An error produced while reading an XML document. Positions are byte offsets into the input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum XmlError {
+    /// The document ended inside a tag, comment or element.
+    UnexpectedEnd,
+    /// A character that isn't allowed at this point was found.
+    UnexpectedCharacter { character: char, position: usize },
+    /// A closing tag didn't match the element it was closing.
+    MismatchedTag {
+        expected: String,
+        found: String,
+        position: usize,
+    },
+    /// An `&...;` entity reference wasn't recognised.
+    InvalidEntity { position: usize },
+    /// The document didn't contain a root element.
+    MissingRoot,
+    /// Elements were nested more deeply than the parser allows.
+    TooDeep { position: usize },
+}
+
+impl fmt::Display for XmlError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            XmlError::UnexpectedEnd => write!(f, "unexpected end of XML document"),
+            XmlError::UnexpectedCharacter {
+                character,
+                position,
+            } => write!(
+                f,
+                "unexpected character {:?} at position {}",
+                character, position
+            ),
+            XmlError::MismatchedTag {
+                expected,
+                found,
+                position,
+            } => write!(
+                f,
+                "expected closing tag </{}>, found </{}> at position {}",
+                expected, found, position
+            ),
+            XmlError::InvalidEntity { position } => {
+                write!(f, "invalid entity reference at position {}", position)
+            }
+            XmlError::MissingRoot => write!(f, "XML document has no root element"),
+            XmlError::TooDeep { position } => {
+                write!(f, "elements nested too deeply at position {}", position)
+            }
+        }
+    }
+}
+
+impl std::error::Error for XmlError {}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Node {
+    Element(Element),
+    Text(String),
+}
+
+/// An element with its namespace prefix removed from the tag and attribute names.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub(crate) struct Element {
+    pub name: String,
+    pub attributes: Vec<(String, String)>,
+    pub children: Vec<Node>,
+}
+
+impl Element {
+    pub fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    pub fn elements(&self) -> impl Iterator<Item = &Element> {
+        self.children.iter().filter_map(|child| match child {
+            Node::Element(element) => Some(element),
+            Node::Text(_) => None,
+        })
+    }
+
+    pub fn children_named<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Element> {
+        self.elements().filter(move |element| element.name == name)
+    }
+
+    pub fn child(&self, name: &str) -> Option<&Element> {
+        self.elements().find(|element| element.name == name)
+    }
+
+    /// Returns every element below this one with the given name, depth first.
+    pub fn descendants_named<'a>(&'a self, name: &str, found: &mut Vec<&'a Element>) {
+        let mut stack = vec![self.elements()];
+        while let Some(elements) = stack.last_mut() {
+            match elements.next() {
+                Some(element) => {
+                    if element.name == name {
+                        found.push(element);
+                    }
+                    stack.push(element.elements());
+                }
+                None => {
+                    stack.pop();
+                }
+            }
+        }
+    }
+
+    /// The concatenated text content of this element and its children.
+    pub fn text(&self) -> String {
+        let mut text = String::new();
+        let mut stack = vec![self.children.iter()];
+        while let Some(children) = stack.last_mut() {
+            match children.next() {
+                Some(Node::Text(value)) => text.push_str(value),
+                Some(Node::Element(element)) => stack.push(element.children.iter()),
+                None => {
+                    stack.pop();
+                }
+            }
+        }
+        text
+    }
+
+    /// The trimmed text of a child element, if it exists and isn't blank.
+    pub fn child_text(&self, name: &str) -> Option<String> {
+        self.child(name)
+            .map(|child| child.text().trim().to_string())
+            .filter(|text| !text.is_empty())
+    }
+}
+
+fn local_name(name: &str) -> &str {
+    name.rsplit(':').next().unwrap_or(name)
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    position: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn rest(&self) -> &'a str {
+        &self.input[self.position..]
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    fn unexpected(&self) -> XmlError {
+        match self.peek() {
+            Some(character) => XmlError::UnexpectedCharacter {
+                character,
+                position: self.position,
+            },
+            None => XmlError::UnexpectedEnd,
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.position += rest.len() - rest.trim_start().len();
+    }
+
+    /// Skips past the next occurrence of `terminator`.
+    fn skip_past(&mut self, terminator: &str) -> Result<&'a str, XmlError> {
+        let rest = self.rest();
+        let end = rest.find(terminator).ok_or(XmlError::UnexpectedEnd)?;
+        self.position += end + terminator.len();
+        Ok(&rest[..end])
+    }
+
+    fn name(&mut self) -> Result<&'a str, XmlError> {
+        let rest = self.rest();
+        let len = rest
+            .find(|c: char| c.is_whitespace() || matches!(c, '/' | '>' | '=' | '?'))
+            .unwrap_or(rest.len());
+        if len == 0 {
+            return Err(self.unexpected());
+        }
+        self.position += len;
+        Ok(&rest[..len])
+    }
+
+    fn expect(&mut self, character: char) -> Result<(), XmlError> {
+        if self.peek() == Some(character) {
+            self.position += character.len_utf8();
+            Ok(())
+        } else {
+            Err(self.unexpected())
+        }
+    }
+
+    fn unescape(&self, text: &str, offset: usize) -> Result<String, XmlError> {
+        let mut output = String::with_capacity(text.len());
+        let mut rest = text;
+        while let Some(start) = rest.find('&') {
+            output.push_str(&rest[..start]);
+            let position = offset + (text.len() - rest.len()) + start;
+            let end = rest[start..]
+                .find(';')
+                .ok_or(XmlError::InvalidEntity { position })?;
+            let entity = &rest[start + 1..start + end];
+            let character = match entity {
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "amp" => Some('&'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                _ => entity
+                    .strip_prefix("#x")
+                    .map(|hex| u32::from_str_radix(hex, 16).ok())
+                    .unwrap_or_else(|| entity.strip_prefix('#').and_then(|n| n.parse().ok()))
+                    .and_then(char::from_u32),
+            };
+            output.push(character.ok_or(XmlError::InvalidEntity { position })?);
+            rest = &rest[start + end + 1..];
+        }
+        output.push_str(rest);
+        Ok(output)
+    }
+
+    /// Skips the prolog, comments, processing instructions and doctype declarations.
+    fn skip_misc(&mut self) -> Result<(), XmlError> {
+        loop {
+            self.skip_whitespace();
+            let rest = self.rest();
+            if rest.starts_with("<?") {
+                self.skip_past("?>")?;
+            } else if rest.starts_with("<!--") {
+                self.skip_past("-->")?;
+            } else if rest.starts_with("<!") {
+                self.skip_past(">")?;
+            } else {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Parses a start tag, returning its name, the empty element, and whether it closed itself.
+    fn start_tag(&mut self) -> Result<(&'a str, Element, bool), XmlError> {
+        self.expect('<')?;
+        let tag = self.name()?;
+        let mut element = Element {
+            name: local_name(tag).into(),
+            ..Element::default()
+        };
+
+        loop {
+            self.skip_whitespace();
+            match self.peek() {
+                Some('/') => {
+                    self.position += 1;
+                    self.expect('>')?;
+                    return Ok((tag, element, true));
+                }
+                Some('>') => {
+                    self.position += 1;
+                    return Ok((tag, element, false));
+                }
+                Some(_) => {
+                    let key = self.name()?;
+                    self.skip_whitespace();
+                    self.expect('=')?;
+                    self.skip_whitespace();
+                    let quote = match self.peek() {
+                        Some(quote @ ('"' | '\'')) => quote,
+                        _ => return Err(self.unexpected()),
+                    };
+                    self.position += 1;
+                    let offset = self.position;
+                    let raw = self.skip_past(if quote == '"' { "\"" } else { "'" })?;
+                    let value = self.unescape(raw, offset)?;
+                    if key != "xmlns" && !key.starts_with("xmlns:") {
+                        element.attributes.push((local_name(key).into(), value));
+                    }
+                }
+                None => return Err(XmlError::UnexpectedEnd),
+            }
+        }
+    }
+
+    /// Parses an element and everything inside it. Open elements are kept on a stack rather
+    /// than parsed recursively, so nesting can't overflow the call stack.
+    fn element(&mut self) -> Result<Element, XmlError> {
+        let (tag, root, closed) = self.start_tag()?;
+        if closed {
+            return Ok(root);
+        }
+        let mut open = vec![(tag, root)];
+
+        while let Some((tag, element)) = open.last_mut() {
+            let rest = self.rest();
+            if rest.is_empty() {
+                return Err(XmlError::UnexpectedEnd);
+            } else if rest.starts_with("</") {
+                let position = self.position;
+                self.position += 2;
+                let closing = self.name()?;
+                self.skip_whitespace();
+                self.expect('>')?;
+                if closing != *tag {
+                    return Err(XmlError::MismatchedTag {
+                        expected: (*tag).into(),
+                        found: closing.into(),
+                        position,
+                    });
+                }
+                let (_, finished) = open.pop().ok_or(XmlError::UnexpectedEnd)?;
+                match open.last_mut() {
+                    Some((_, parent)) => parent.children.push(Node::Element(finished)),
+                    None => return Ok(finished),
+                }
+            } else if rest.starts_with("<!--") {
+                self.skip_past("-->")?;
+            } else if rest.starts_with("<![CDATA[") {
+                self.position += "<![CDATA[".len();
+                let text = self.skip_past("]]>")?;
+                element.children.push(Node::Text(text.into()));
+            } else if rest.starts_with("<?") {
+                self.skip_past("?>")?;
+            } else if rest.starts_with('<') {
+                let position = self.position;
+                let (tag, child, closed) = self.start_tag()?;
+                if closed {
+                    element.children.push(Node::Element(child));
+                } else if open.len() >= MAX_DEPTH {
+                    return Err(XmlError::TooDeep { position });
+                } else {
+                    open.push((tag, child));
+                }
+            } else {
+                let offset = self.position;
+                let len = rest.find('<').unwrap_or(rest.len());
+                self.position += len;
+                let text = self.unescape(&rest[..len], offset)?;
+                element.children.push(Node::Text(text));
+            }
+        }
+        Err(XmlError::UnexpectedEnd)
+    }
+}
+
+/// Parses a document and returns its root element.
+pub(crate) fn parse(input: &str) -> Result<Element, XmlError> {
+    let mut parser = Parser { input, position: 0 };
+    parser.skip_misc()?;
+    if parser.peek().is_none() {
+        return Err(XmlError::MissingRoot);
+    }
+    let root = parser.element()?;
+    parser.skip_misc()?;
+    match parser.peek() {
+        Some(_) => Err(parser.unexpected()),
+        None => Ok(root),
+    }
+}
+
+/// Escapes text for use in element content or a double quoted attribute.
+pub(crate) fn escape(text: &str) -> String {
+    let mut output = String::with_capacity(text.len());
+    for character in text.chars() {
+        match character {
+            '<' => output.push_str("&lt;"),
+            '>' => output.push_str("&gt;"),
+            '&' => output.push_str("&amp;"),
+            '"' => output.push_str("&quot;"),
+            '\'' => output.push_str("&apos;"),
+            _ => output.push(character),
+        }
+    }
+    output
+}